@@ -1,84 +1,464 @@
-use developer_tools::view::{
-    calculator_view::CalcView, datetime_view::DateTimeConverterView, differ_view::DifferenceView,
-    formatter_view::FormatterView, game_of_life::GameOfLifeView, hash_view::HashView,
-    number_view::NumberBaseConverterView, View,
+use developer_tools::config::keymap::{Command, Keymap};
+use developer_tools::config::{fonts, AppStyle, Settings};
+use developer_tools::model::{detect_content, ContentMatch};
+use developer_tools::tools::{
+    SavedWorkspace, ToolCategory, ToolEntry, ToolError, ToolRegistry, Workspace,
 };
+use developer_tools::view::command_palette::{CommandPalette, PaletteItem};
+use developer_tools::view::settings_view::SettingsView;
 use eframe::egui;
-use egui::{Color32, RichText, TextStyle};
+use egui::RichText;
+use serde::{Deserialize, Serialize};
 // use webbrowser;
 
-#[derive(Debug, Clone, PartialEq)]
-enum DeveloperTools {
-    Hashing(HashView),
-    Calculator(CalcView),
-    TextDifference(DifferenceView),
-    JsonFormatter(FormatterView),
-    SqlFormatter(FormatterView),
-    DateTimeConverter(DateTimeConverterView),
-    NumberBaseConverter(NumberBaseConverterView),
-    GameOfLife(GameOfLifeView),
-    // MOFish(StockView),
+/// What the app keeps in eframe's storage between runs. Window geometry is
+/// part of egui's own memory, which eframe saves as well.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedState {
+    workspace: SavedWorkspace,
+    /// Name of the theme.
+    theme: Option<String>,
 }
 
-impl Default for DeveloperTools {
-    fn default() -> Self {
-        Self::Hashing(HashView::new())
-    }
-}
-
-impl DeveloperTools {
-    fn name(&self) -> &str {
-        match self {
-            DeveloperTools::Hashing(_v) => "Hashing",
-            DeveloperTools::Calculator(_v) => "Calculator",
-            DeveloperTools::TextDifference(_v) => "Text Difference",
-            // DeveloperTools::Base64 => "Base64",
-            DeveloperTools::JsonFormatter(_v) => "Json Formatter",
-            DeveloperTools::SqlFormatter(_v) => "Sql Formatter",
-            DeveloperTools::DateTimeConverter(_v) => "Date Time Converter",
-            DeveloperTools::NumberBaseConverter(_v) => "Number Base Converter",
-            DeveloperTools::GameOfLife(_v) => "Game of Life",
-            // DeveloperTools::MOFish(_v) => "mo fish",
-        }
-    }
-
-    fn view(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
-        match self {
-            DeveloperTools::Hashing(v) => v.render(ctx, ui),
-            DeveloperTools::Calculator(v) => v.render(ctx, ui),
-            DeveloperTools::JsonFormatter(v) => v.render(ctx, ui),
-            DeveloperTools::SqlFormatter(v) => v.render(ctx, ui),
-            DeveloperTools::DateTimeConverter(v) => v.render(ctx, ui),
-            DeveloperTools::NumberBaseConverter(v) => v.render(ctx, ui),
-            DeveloperTools::TextDifference(v) => v.render(ctx, ui),
-            DeveloperTools::GameOfLife(v) => v.render(ctx, ui),
-            // DeveloperTools::MOFish(v) => {}
-        }
-    }
+/// Pasted text waiting for the user to pick a tool.
+struct SmartPaste {
+    text: String,
+    matches: Vec<ContentMatch>,
 }
 
 // 主应用结构
 
 pub struct App {
-    selected_tool_index: Option<usize>, // 使用索引代替克隆
-    developer_tools: Vec<DeveloperTools>,
+    registry: ToolRegistry,
+    /// Open tabs, any tool can be open in several of them.
+    workspace: Workspace,
+    /// Id of the tab whose title is being edited, and the edited title.
+    renaming: Option<(u64, String)>,
+    keymap: Keymap,
+    palette: CommandPalette,
+    /// Built-in themes, then those of the themes directory.
+    themes: Vec<AppStyle>,
+    /// Name of the applied theme.
+    theme: String,
+    /// Fonts and scale, kept in the settings file rather than eframe's
+    /// storage since the window size is needed before the app starts.
+    settings: Settings,
+    settings_view: SettingsView,
+    /// Error of the last command, or of loading the keymap, themes or
+    /// settings.
+    status: Option<String>,
+    /// Pass number of the smart paste command, until the clipboard content
+    /// arrives or it is clear none will.
+    awaiting_paste: Option<u64>,
+    smart_paste: Option<SmartPaste>,
 }
 
 impl App {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self {
-            selected_tool_index: None,
-            developer_tools: vec![
-                DeveloperTools::Hashing(HashView::new()),
-                DeveloperTools::Calculator(CalcView::default()),
-                DeveloperTools::JsonFormatter(FormatterView::new("json")),
-                DeveloperTools::SqlFormatter(FormatterView::new("sql")),
-                DeveloperTools::DateTimeConverter(DateTimeConverterView::new()),
-                DeveloperTools::NumberBaseConverter(NumberBaseConverterView::new()),
-                DeveloperTools::TextDifference(DifferenceView::new()),
-                DeveloperTools::GameOfLife(GameOfLifeView::default()),
-                // DeveloperTools::MOFish(StockView::new()),
-            ],
+    pub fn new(
+        cc: &eframe::CreationContext<'_>,
+        settings: Settings,
+        settings_error: Option<String>,
+    ) -> Self {
+        let registry = ToolRegistry::builtin();
+        // every file that failed to load is reported
+        let mut errors: Vec<String> = settings_error.into_iter().collect();
+        let keymap = match Keymap::file().map(|path| Keymap::load(&path)) {
+            Some(Ok(keymap)) => keymap,
+            Some(Err(e)) => {
+                errors.push(e);
+                Keymap::default()
+            }
+            None => Keymap::default(),
+        };
+        if let Err(e) = fonts::setup_fonts(&cc.egui_ctx, &settings) {
+            errors.push(e);
+        }
+        let mut themes = AppStyle::builtin();
+        if let Some(dir) = AppStyle::dir() {
+            let (custom, theme_errors) = AppStyle::load_dir(&dir);
+            // a theme file can replace a built-in theme of the same name
+            for style in custom {
+                themes.retain(|theme| theme.name != style.name);
+                themes.push(style);
+            }
+            errors.extend(theme_errors);
+        }
+        let theme = themes[0].name.clone();
+        let mut app = Self {
+            registry,
+            workspace: Workspace::new(),
+            renaming: None,
+            keymap,
+            palette: CommandPalette::default(),
+            themes,
+            theme,
+            settings,
+            settings_view: SettingsView::default(),
+            status: (!errors.is_empty()).then(|| errors.join("\n")),
+            awaiting_paste: None,
+            smart_paste: None,
+        };
+        if let Some(saved) = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<SavedState>(storage, eframe::APP_KEY))
+        {
+            app.restore(saved);
+        }
+        let theme = app.theme.clone();
+        app.set_theme(&theme, &cc.egui_ctx);
+        app
+    }
+
+    fn restore(&mut self, saved: SavedState) {
+        self.workspace = Workspace::restore(&self.registry, saved.workspace);
+        if let Some(theme) = saved.theme {
+            self.theme = theme;
+        }
+    }
+
+    fn saved_state(&self) -> SavedState {
+        SavedState {
+            workspace: self.workspace.save(),
+            theme: Some(self.theme.clone()),
+        }
+    }
+
+    /// Applies the theme called `name`, or the default one when there is no
+    /// such theme any more.
+    fn set_theme(&mut self, name: &str, ctx: &egui::Context) {
+        let style = self
+            .themes
+            .iter()
+            .find(|theme| theme.name == name)
+            .unwrap_or(&self.themes[0]);
+        style.apply(ctx);
+        self.theme = style.name.clone();
+    }
+
+    /// Installs `settings` and writes them to the settings file.
+    fn apply_settings(&mut self, settings: Settings, ctx: &egui::Context) {
+        let mut errors: Vec<String> = fonts::setup_fonts(ctx, &settings)
+            .err()
+            .into_iter()
+            .collect();
+        if let Some(path) = Settings::file() {
+            errors.extend(settings.save(&path).err());
+        }
+        self.status = (!errors.is_empty()).then(|| errors.join("\n"));
+        self.settings = settings;
+    }
+
+    /// Closes every tab and resets egui's memory and the theme, the next
+    /// save overwrites the stored data.
+    fn clear_saved_data(&mut self, ctx: &egui::Context) {
+        self.workspace.clear();
+        self.renaming = None;
+        // the options hold the fonts' text styles, which are not saved data
+        ctx.memory_mut(|memory| {
+            let options = memory.options.clone();
+            *memory = Default::default();
+            memory.options = options;
+        });
+        let theme = self.themes[0].name.clone();
+        self.set_theme(&theme, ctx);
+    }
+
+    /// Registry entry of the current tab.
+    fn active_entry(&self) -> Option<&ToolEntry> {
+        self.registry.get(&self.workspace.active_tab()?.tool_id)
+    }
+
+    /// Registry indices in the order the sidebar lists them.
+    fn sidebar_order(&self) -> Vec<usize> {
+        ToolCategory::iter()
+            .flat_map(|category| {
+                self.registry
+                    .iter()
+                    .enumerate()
+                    .filter(move |(_, entry)| entry.category == *category)
+                    .map(|(index, _)| index)
+            })
+            .collect()
+    }
+
+    /// Moves to the next tool of the sidebar, in a tab of its own.
+    fn cycle_tool(&mut self, step: isize) {
+        let order = self.sidebar_order();
+        let current = self.active_entry().map(|entry| entry.id);
+        let position = order
+            .iter()
+            .position(|&index| self.registry.iter().nth(index).map(|e| e.id) == current);
+        let next = match position {
+            Some(position) => (position as isize + step).rem_euclid(order.len() as isize),
+            None if step > 0 => 0,
+            None => order.len() as isize - 1,
+        };
+        if let Some(entry) = order
+            .get(next as usize)
+            .and_then(|&index| self.registry.iter().nth(index))
+        {
+            self.workspace.focus_or_open(entry);
+        }
+    }
+
+    fn close_tab(&mut self, index: usize) {
+        self.workspace.close(index);
+        self.renaming = None;
+    }
+
+    fn run_command(&mut self, command: Command, ctx: &egui::Context) {
+        self.status = None;
+        let result = match command {
+            Command::CommandPalette => {
+                self.palette.toggle();
+                Ok(())
+            }
+            Command::SmartPaste => {
+                self.awaiting_paste = Some(ctx.cumulative_pass_nr());
+                ctx.send_viewport_cmd(egui::ViewportCommand::RequestPaste);
+                ctx.request_repaint();
+                Ok(())
+            }
+            Command::OpenSettings => {
+                self.settings_view.open(&self.settings);
+                Ok(())
+            }
+            Command::NextTool => {
+                self.cycle_tool(1);
+                Ok(())
+            }
+            Command::PreviousTool => {
+                self.cycle_tool(-1);
+                Ok(())
+            }
+            Command::NextTab => {
+                self.workspace.cycle(1);
+                Ok(())
+            }
+            Command::PreviousTab => {
+                self.workspace.cycle(-1);
+                Ok(())
+            }
+            Command::CloseTab => {
+                if let Some(index) = self.workspace.active() {
+                    self.close_tab(index);
+                }
+                Ok(())
+            }
+            Command::OpenTool(id) | Command::NewTab(id) if self.registry.get(&id).is_none() => {
+                Err(ToolError::StateError(format!("unknown tool {}", id)))
+            }
+            Command::OpenTool(id) => {
+                self.workspace
+                    .focus_or_open(self.registry.get(&id).unwrap());
+                Ok(())
+            }
+            Command::NewTab(id) => {
+                self.workspace.open(self.registry.get(&id).unwrap());
+                Ok(())
+            }
+            command => {
+                let Some(tab) = self.workspace.active_tab_mut() else {
+                    return;
+                };
+                let tool = &mut tab.tool;
+                match command {
+                    Command::Run if tool.validate() => tool.process(),
+                    Command::Clear => {
+                        tool.clear();
+                        Ok(())
+                    }
+                    Command::CopyResult => {
+                        if let Some(output) = tool.output() {
+                            ctx.copy_text(output);
+                        }
+                        Ok(())
+                    }
+                    // actions bound in the keymap only apply to their own tool
+                    Command::ToolAction {
+                        tool: target,
+                        action,
+                    } if tab.tool_id == target => tool.run_action(&action),
+                    _ => Ok(()),
+                }
+            }
+        };
+        if let Err(e) = result {
+            self.status = Some(e.to_string());
+        }
+    }
+
+    fn palette_items(&self, ctx: &egui::Context) -> Vec<PaletteItem<Command>> {
+        let mut items = Vec::new();
+        let mut push = |label: String, detail: &str, command: Command| {
+            items.push(PaletteItem {
+                label,
+                detail: detail.to_string(),
+                shortcut: self
+                    .keymap
+                    .shortcut(&command)
+                    .map(|shortcut| ctx.format_shortcut(shortcut)),
+                value: command,
+            });
+        };
+
+        // commands of the current tool come first
+        if let (Some(entry), Some(tab)) = (self.active_entry(), self.workspace.active_tab()) {
+            let tool = &tab.tool;
+            let label = |name: &str| format!("{}: {}", entry.name, name);
+            push(
+                label(tool.process_label()),
+                &entry.description,
+                Command::Run,
+            );
+            for action in tool.actions() {
+                let command = Command::ToolAction {
+                    tool: entry.id.to_string(),
+                    action: action.id.to_string(),
+                };
+                push(label(action.name), &entry.description, command);
+            }
+            if tool.output().is_some() {
+                push(
+                    label("Copy result"),
+                    &entry.description,
+                    Command::CopyResult,
+                );
+            }
+            push(label("Clear"), &entry.description, Command::Clear);
+            push(format!("Close {}", tab.title), "", Command::CloseTab);
+        }
+
+        for index in self.sidebar_order() {
+            let entry = self.registry.iter().nth(index).unwrap();
+            let command = Command::OpenTool(entry.id.to_string());
+            push(format!("Open {}", entry.name), &entry.description, command);
+            let command = Command::NewTab(entry.id.to_string());
+            push(
+                format!("New {} tab", entry.name),
+                &entry.description,
+                command,
+            );
+        }
+        push(
+            "Smart paste".to_string(),
+            "Open the clipboard in the tool that fits it",
+            Command::SmartPaste,
+        );
+        push("Next tool".to_string(), "", Command::NextTool);
+        push("Previous tool".to_string(), "", Command::PreviousTool);
+        push("Next tab".to_string(), "", Command::NextTab);
+        push("Previous tab".to_string(), "", Command::PreviousTab);
+        push(
+            "Settings".to_string(),
+            "UI scale and fonts",
+            Command::OpenSettings,
+        );
+        items
+    }
+
+    /// Takes a paste event meant for smart paste: one requested by the smart
+    /// paste command, or any paste while no text field has focus.
+    fn take_paste(&mut self, ctx: &egui::Context) -> Option<String> {
+        let focused = ctx.memory(|memory| memory.focused().is_some());
+        if focused && self.awaiting_paste.is_none() {
+            return None;
+        }
+        let text = ctx.input_mut(|input| {
+            let index = input
+                .events
+                .iter()
+                .position(|event| matches!(event, egui::Event::Paste(_)))?;
+            match input.events.remove(index) {
+                egui::Event::Paste(text) => Some(text),
+                _ => None,
+            }
+        });
+        if text.is_some() {
+            self.awaiting_paste = None;
+            return text;
+        }
+        // the paste comes the frame after the request, but eframe sends none
+        // for an empty or non-text clipboard
+        let requested = self.awaiting_paste?;
+        if ctx.cumulative_pass_nr() > requested + 1 {
+            self.awaiting_paste = None;
+            Some(String::new())
+        } else {
+            ctx.request_repaint();
+            None
+        }
+    }
+
+    /// Opens the tool for `content` with `text` as its input. A tab of that
+    /// tool is reused only while it has no input, so no work is overwritten.
+    fn route_paste(&mut self, content: ContentMatch, text: &str) {
+        let Some(entry) = self.registry.get(content.kind.tool_id()) else {
+            return;
+        };
+        let index = self.workspace.focus_or_open(entry);
+        if self.workspace.tabs()[index].tool.validate() {
+            self.workspace.open(entry);
+        }
+        let Some(tab) = self.workspace.active_tab_mut() else {
+            return;
+        };
+        let tool = &mut tab.tool;
+        tool.set_input(text);
+        if tool.validate() {
+            if let Err(e) = tool.process() {
+                self.status = Some(e.to_string());
+            }
+        }
+    }
+
+    fn render_smart_paste(&mut self, ctx: &egui::Context) {
+        let Some(paste) = &self.smart_paste else {
+            return;
+        };
+        let mut open = true;
+        let mut chosen = ctx
+            .input_mut(|input| input.consume_key(egui::Modifiers::NONE, egui::Key::Enter))
+            .then(|| paste.matches.first().copied())
+            .flatten();
+        egui::Window::new("Smart paste")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .show(ctx, |ui| {
+                let preview: String = paste.text.trim().chars().take(200).collect();
+                ui.label(RichText::new(preview).monospace().weak());
+                ui.separator();
+                if paste.matches.is_empty() {
+                    ui.label("The clipboard is empty");
+                }
+                for (rank, content) in paste.matches.iter().enumerate() {
+                    let Some(entry) = self.registry.get(content.kind.tool_id()) else {
+                        continue;
+                    };
+                    let text = format!("{}  →  {} {}", content.kind.name(), entry.icon, entry.name);
+                    let text = if rank == 0 {
+                        RichText::new(text).strong()
+                    } else {
+                        RichText::new(text)
+                    };
+                    ui.horizontal(|ui| {
+                        if ui.button(text).clicked() {
+                            chosen = Some(*content);
+                        }
+                        ui.weak(format!("{}%", content.confidence));
+                    });
+                }
+            });
+
+        if let Some(content) = chosen {
+            let text = paste.text.clone();
+            self.route_paste(content, &text);
+            self.smart_paste = None;
+        } else if !open || ctx.input(|input| input.key_pressed(egui::Key::Escape)) {
+            self.smart_paste = None;
         }
     }
 
@@ -86,29 +466,175 @@ impl App {
         ui.vertical(|ui| {
             ui.label(
                 RichText::new("Developer Tools")
-                    .text_style(TextStyle::Name("logo".into()))
-                    .color(Color32::from_rgb(144, 125, 172)),
+                    .text_style(fonts::logo())
+                    .color(AppStyle::current(ui.ctx()).accent),
             );
             ui.separator();
 
             // 工具列表区域
             ui.with_layout(egui::Layout::top_down_justified(egui::Align::LEFT), |ui| {
-                for (index, tool) in self.developer_tools.iter().enumerate() {
-                    if ui
-                        .selectable_label(
-                            self.selected_tool_index.map_or(false, |s| s == index),
-                            RichText::new(tool.name()).size(12.0),
-                        )
-                        .clicked()
-                    {
-                        self.selected_tool_index = Some(index);
+                for category in ToolCategory::iter() {
+                    let mut entries = self
+                        .registry
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, entry)| entry.category == *category)
+                        .peekable();
+                    if entries.peek().is_none() {
+                        continue;
+                    }
+
+                    ui.label(RichText::new(category.name()).small().weak());
+                    ui.add_space(4.0);
+                    let current = self.workspace.active_tab().map(|tab| tab.tool_id.as_str());
+                    let mut open = None;
+                    for (_, entry) in entries {
+                        let response = ui
+                            .selectable_label(
+                                current == Some(entry.id),
+                                RichText::new(format!("{}  {}", entry.icon, entry.name)).size(12.0),
+                            )
+                            .on_hover_text(format!(
+                                "{}\nCtrl+click or middle click opens a new tab",
+                                entry.description
+                            ));
+                        // a plain click goes to the open tab of the tool
+                        if response.clicked() {
+                            let new_tab = ui.input(|input| input.modifiers.command);
+                            open = Some((entry, new_tab));
+                        }
+                        if response.middle_clicked() {
+                            open = Some((entry, true));
+                        }
+                        response.context_menu(|ui| {
+                            if ui.button("Open in new tab").clicked() {
+                                open = Some((entry, true));
+                                ui.close_menu();
+                            }
+                        });
+                        ui.add_space(8.0);
+                    }
+                    match open {
+                        Some((entry, true)) => {
+                            self.workspace.open(entry);
+                        }
+                        Some((entry, false)) => {
+                            self.workspace.focus_or_open(entry);
+                        }
+                        None => {}
                     }
-                    ui.add_space(8.0);
                 }
             });
         });
     }
 
+    /// Tab strip: click to focus, double click to rename, drag to reorder,
+    /// middle click or ✖ to close. The context menu has all of these too.
+    fn render_tabs(&mut self, ui: &mut egui::Ui) {
+        enum TabEvent {
+            Focus(usize),
+            Close(usize),
+            Move(usize, usize),
+            Rename(usize),
+        }
+        let mut events = Vec::new();
+        let mut finished_rename = None;
+        let active = self.workspace.active();
+        let count = self.workspace.len();
+
+        egui::ScrollArea::horizontal()
+            .id_salt("tabs")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    for (index, tab) in self.workspace.tabs().iter().enumerate() {
+                        if let Some((id, title)) = &mut self.renaming {
+                            if *id == tab.id {
+                                let response =
+                                    ui.add(egui::TextEdit::singleline(title).desired_width(120.0));
+                                response.request_focus();
+                                if ui.input(|input| input.key_pressed(egui::Key::Escape)) {
+                                    finished_rename = Some(None);
+                                } else if response.lost_focus() {
+                                    finished_rename = Some(Some(index));
+                                }
+                                continue;
+                            }
+                        }
+
+                        let drag_id = egui::Id::new(("tab", tab.id));
+                        let response = ui
+                            .dnd_drag_source(drag_id, index, |ui| {
+                                ui.selectable_label(active == Some(index), &tab.title)
+                            })
+                            .inner;
+                        let response = match self.registry.get(&tab.tool_id) {
+                            Some(entry) => response.on_hover_text(&entry.name),
+                            None => response,
+                        };
+                        if response.clicked() {
+                            events.push(TabEvent::Focus(index));
+                        }
+                        if response.double_clicked() {
+                            events.push(TabEvent::Rename(index));
+                        }
+                        if response.middle_clicked() {
+                            events.push(TabEvent::Close(index));
+                        }
+                        if let Some(from) = response.dnd_release_payload::<usize>() {
+                            events.push(TabEvent::Move(*from, index));
+                        }
+                        response.context_menu(|ui| {
+                            let mut item = |ui: &mut egui::Ui, enabled, text, event| {
+                                if ui.add_enabled(enabled, egui::Button::new(text)).clicked() {
+                                    events.push(event);
+                                    ui.close_menu();
+                                }
+                            };
+                            item(ui, true, "Rename", TabEvent::Rename(index));
+                            item(
+                                ui,
+                                index > 0,
+                                "Move left",
+                                TabEvent::Move(index, index.saturating_sub(1)),
+                            );
+                            item(
+                                ui,
+                                index + 1 < count,
+                                "Move right",
+                                TabEvent::Move(index, index + 1),
+                            );
+                            item(ui, true, "Close", TabEvent::Close(index));
+                        });
+                        if ui.small_button("✖").on_hover_text("Close tab").clicked() {
+                            events.push(TabEvent::Close(index));
+                        }
+                        ui.add_space(6.0);
+                    }
+                });
+            });
+
+        match finished_rename {
+            Some(Some(index)) => {
+                if let Some((_, title)) = self.renaming.take() {
+                    self.workspace.rename(index, &title);
+                }
+            }
+            Some(None) => self.renaming = None,
+            None => {}
+        }
+        for event in events {
+            match event {
+                TabEvent::Focus(index) => self.workspace.focus(index),
+                TabEvent::Close(index) => self.close_tab(index),
+                TabEvent::Move(from, to) => self.workspace.move_tab(from, to),
+                TabEvent::Rename(index) => {
+                    let tab = &self.workspace.tabs()[index];
+                    self.renaming = Some((tab.id, tab.title.clone()));
+                }
+            }
+        }
+    }
+
     pub fn render_main_panel(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
         ui.vertical_centered_justified(|ui| {
             //top
@@ -125,17 +651,88 @@ impl App {
                             });
                         });
                     }
-                    egui::global_theme_preference_buttons(ui);
+                    let mut theme = None;
+                    egui::ComboBox::from_id_salt("theme")
+                        .selected_text(format!("🎨 {}", self.theme))
+                        .show_ui(ui, |ui| {
+                            for style in &self.themes {
+                                let selected = style.name == self.theme;
+                                if ui.selectable_label(selected, &style.name).clicked() {
+                                    theme = Some(style.name.clone());
+                                }
+                            }
+                        })
+                        .response
+                        .on_hover_text(match AppStyle::dir() {
+                            Some(dir) => format!("Theme files go in {}", dir.display()),
+                            None => "Theme".to_string(),
+                        });
+                    if let Some(theme) = theme {
+                        self.set_theme(&theme, ctx);
+                    }
+                    let palette = self
+                        .keymap
+                        .shortcut(&Command::CommandPalette)
+                        .map(|shortcut| format!(" ({})", ctx.format_shortcut(shortcut)))
+                        .unwrap_or_default();
+                    let smart_paste = self
+                        .keymap
+                        .shortcut(&Command::SmartPaste)
+                        .map(|shortcut| format!(" ({})", ctx.format_shortcut(shortcut)))
+                        .unwrap_or_default();
+                    if ui
+                        .button("📋")
+                        .on_hover_text(format!("Smart paste{}", smart_paste))
+                        .clicked()
+                    {
+                        self.run_command(Command::SmartPaste, ctx);
+                    }
+                    if ui
+                        .button("🔍")
+                        .on_hover_text(format!("Command palette{}", palette))
+                        .clicked()
+                    {
+                        self.palette.toggle();
+                    }
+                    let settings = self
+                        .keymap
+                        .shortcut(&Command::OpenSettings)
+                        .map(|shortcut| format!(" ({})", ctx.format_shortcut(shortcut)))
+                        .unwrap_or_default();
+                    if ui
+                        .button("⚙")
+                        .on_hover_text(format!("Settings{}", settings))
+                        .clicked()
+                    {
+                        self.run_command(Command::OpenSettings, ctx);
+                    }
+                    if ui.button("🗑").on_hover_text("Clear saved data").clicked() {
+                        self.clear_saved_data(ctx);
+                    }
                 });
             });
 
+            if let Some(status) = &self.status {
+                let mut dismissed = false;
+                ui.horizontal(|ui| {
+                    ui.colored_label(ui.visuals().error_fg_color, status);
+                    dismissed = ui.small_button("✖").clicked();
+                });
+                if dismissed {
+                    self.status = None;
+                }
+            }
+
+            if !self.workspace.is_empty() {
+                self.render_tabs(ui);
+                ui.separator();
+            }
             ui.add_space(15.0);
 
             // Main content area
-            if let Some(index) = self.selected_tool_index {
-                if let Some(tool) = self.developer_tools.get_mut(index) {
-                    tool.view(ctx, ui) // 直接操作原实例
-                }
+            if let Some(tab) = self.workspace.active_tab_mut() {
+                // tabs of the same tool must not share widget state
+                ui.push_id(tab.id, |ui| tab.tool.render(ui));
             }
         });
     }
@@ -143,6 +740,24 @@ impl App {
 
 impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if let Some(command) = self.keymap.pressed(ctx) {
+            self.run_command(command, ctx);
+        }
+        if self.palette.open {
+            let items = self.palette_items(ctx);
+            if let Some(command) = self.palette.show(ctx, items) {
+                self.run_command(command, ctx);
+            }
+        }
+        if let Some(text) = self.take_paste(ctx) {
+            let matches = detect_content(&text);
+            self.smart_paste = Some(SmartPaste { text, matches });
+        }
+        self.render_smart_paste(ctx);
+        if let Some(settings) = self.settings_view.show(ctx) {
+            self.apply_settings(settings, ctx);
+        }
+
         egui::SidePanel::left("sidebar")
             .max_width(172.0)
             .show(ctx, |ui| {
@@ -153,4 +768,8 @@ impl eframe::App for App {
             self.render_main_panel(ctx, ui);
         });
     }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.saved_state());
+    }
 }
@@ -0,0 +1,238 @@
+// fonts and text styles, built from the settings
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    sync::Arc,
+};
+
+use egui::{FontData, FontDefinitions, FontFamily, FontId, TextStyle};
+
+use super::Settings;
+
+pub const PLAY_WRITE_FAMILY_NAME: &str = "Playwrite AU SA";
+pub const HARMONYOS_FAMILY_NAME: &str = "HarmonyOS SANS SC";
+const ALIBABAPUHUITI_FAMILY_NAME: &str = "AlibabaPuHuiTi-3-55-Regular";
+
+pub const UBUNTU: &str = "Ubuntu";
+pub const HACK: &str = "Hack";
+pub const HARMONYOS: &str = "HarmonyOS Sans SC";
+pub const ALIBABA_PUHUITI: &str = "Alibaba PuHuiTi";
+
+/// Fonts that need no file, by the name used in the settings, and their
+/// key in egui's font data.
+pub const BUNDLED_FONTS: [(&str, &str); 4] = [
+    (UBUNTU, "Ubuntu-Light"),
+    (HACK, "Hack"),
+    (HARMONYOS, HARMONYOS_FAMILY_NAME),
+    (ALIBABA_PUHUITI, ALIBABAPUHUITI_FAMILY_NAME),
+];
+
+/// Bundled fonts with CJK glyphs.
+pub const CJK_FONTS: [&str; 2] = [HARMONYOS, ALIBABA_PUHUITI];
+
+/// egui's emoji fonts, kept as fallback for the tool icons.
+const EMOJI_FONTS: [&str; 2] = ["NotoEmoji-Regular", "emoji-icon-font"];
+
+#[inline]
+pub fn logo() -> TextStyle {
+    TextStyle::Name("logo".into())
+}
+
+/// Installs the fonts, text sizes and UI scale of `settings`. A font file
+/// that cannot be used is reported and replaced by the default font.
+pub fn setup_fonts(ctx: &egui::Context, settings: &Settings) -> Result<(), String> {
+    let mut errors = Vec::new();
+    ctx.set_fonts(load_fonts(settings, &mut errors));
+    ctx.set_zoom_factor(settings.ui_scale);
+
+    // other styles keep their size relative to the body text
+    let scale = settings.body_size / 16.0;
+    let proportional = |size: f32| FontId::new(size * scale, FontFamily::Proportional);
+    let playwrite = FontFamily::Name(PLAY_WRITE_FAMILY_NAME.into());
+    let monospace = FontId::new(settings.monospace_size, FontFamily::Monospace);
+    let text_styles: BTreeMap<_, _> = [
+        (TextStyle::Heading, proportional(20.0)),
+        (logo(), FontId::new(25.0 * scale, playwrite.clone())),
+        (
+            TextStyle::Name("SidebarHeader".into()),
+            FontId::new(25.0 * scale, playwrite),
+        ),
+        (TextStyle::Name("Heading2".into()), proportional(25.0)),
+        (TextStyle::Name("Context".into()), proportional(23.0)),
+        (TextStyle::Body, proportional(16.0)),
+        (TextStyle::Monospace, monospace.clone()),
+        (TextStyle::Button, monospace),
+        (TextStyle::Small, proportional(10.0)),
+    ]
+    .into();
+    ctx.all_styles_mut(move |style| style.text_styles = text_styles.clone());
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+fn load_fonts(settings: &Settings, errors: &mut Vec<String>) -> FontDefinitions {
+    let msyh_font = FontData::from_static(include_bytes!(
+        "../../assets/fonts/HarmonyOS_SansSC_Regular.ttf"
+    ));
+    let handwriting_font =
+        FontData::from_static(include_bytes!("../../assets/fonts/Playwrite.ttf"));
+
+    let puhu_font = FontData::from_static(include_bytes!(
+        "../../assets/fonts/AlibabaPuHuiTi-3-55-Regular.ttf"
+    ));
+
+    let mut fonts = FontDefinitions::default();
+
+    fonts
+        .font_data
+        .insert(HARMONYOS_FAMILY_NAME.to_owned(), Arc::from(msyh_font));
+    fonts.font_data.insert(
+        PLAY_WRITE_FAMILY_NAME.to_owned(),
+        Arc::from(handwriting_font),
+    );
+
+    fonts
+        .font_data
+        .insert(ALIBABAPUHUITI_FAMILY_NAME.to_owned(), Arc::from(puhu_font));
+
+    let defaults = Settings::default();
+    // read once, both families use it
+    let fallback = if settings.cjk_fallback.is_empty() {
+        None
+    } else {
+        font_key(&mut fonts, &settings.cjk_fallback, errors)
+    };
+    let mut family = |font: &str, default: &str| {
+        let mut keys = Vec::new();
+        let primary = font_key(&mut fonts, font, errors);
+        keys.extend(primary.or_else(|| font_key(&mut fonts, default, errors)));
+        keys.extend(fallback.clone());
+        keys.extend(EMOJI_FONTS.map(str::to_owned));
+        let mut seen = HashSet::new();
+        keys.retain(|key| seen.insert(key.clone()));
+        keys
+    };
+    let proportional = family(&settings.body_font, &defaults.body_font);
+    let monospace = family(&settings.monospace_font, &defaults.monospace_font);
+    fonts
+        .families
+        .insert(FontFamily::Proportional, proportional);
+    fonts.families.insert(FontFamily::Monospace, monospace);
+
+    fonts.families.insert(
+        FontFamily::Name(PLAY_WRITE_FAMILY_NAME.into()),
+        vec![PLAY_WRITE_FAMILY_NAME.to_owned()],
+    );
+
+    fonts
+}
+
+/// Key of `font` in the font data, reading it first when it is a file.
+fn font_key(fonts: &mut FontDefinitions, font: &str, errors: &mut Vec<String>) -> Option<String> {
+    if let Some((_, key)) = BUNDLED_FONTS.iter().find(|(name, _)| *name == font) {
+        return Some(key.to_string());
+    }
+    if fonts.font_data.contains_key(font) {
+        return Some(font.to_string());
+    }
+    match fs::read(font) {
+        Ok(bytes) if is_font(&bytes) => {
+            fonts
+                .font_data
+                .insert(font.to_string(), Arc::new(FontData::from_owned(bytes)));
+            Some(font.to_string())
+        }
+        Ok(_) => {
+            errors.push(format!("{}: not a valid TrueType or OpenType font", font));
+            None
+        }
+        Err(e) => {
+            errors.push(format!("{}: {}", font, e));
+            None
+        }
+    }
+}
+
+/// Parses the font tables the way egui does, which panics on fonts it
+/// cannot read.
+pub fn is_font(bytes: &[u8]) -> bool {
+    use ab_glyph::Font;
+
+    ab_glyph::FontRef::try_from_slice(bytes).is_ok_and(|font| font.units_per_em().is_some())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_font_families() {
+        let mut errors = Vec::new();
+        let fonts = load_fonts(&Settings::default(), &mut errors);
+        assert!(errors.is_empty());
+        assert_eq!(
+            fonts.families[&FontFamily::Proportional],
+            [
+                HARMONYOS_FAMILY_NAME,
+                "NotoEmoji-Regular",
+                "emoji-icon-font"
+            ]
+        );
+        assert_eq!(
+            fonts.families[&FontFamily::Monospace][..2],
+            ["Hack", HARMONYOS_FAMILY_NAME]
+        );
+
+        // a missing font file falls back to the default font
+        let settings = Settings {
+            monospace_font: "/nonexistent/font.ttf".to_string(),
+            cjk_fallback: String::new(),
+            ..Default::default()
+        };
+        let fonts = load_fonts(&settings, &mut errors);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            fonts.families[&FontFamily::Monospace],
+            ["Hack", "NotoEmoji-Regular", "emoji-icon-font"]
+        );
+
+        // so does a font file that is cut short
+        let playwrite = include_bytes!("../../assets/fonts/Playwrite.ttf");
+        let dir = std::env::temp_dir().join(format!("font-files-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let truncated = dir.join("truncated.ttf");
+        fs::write(&truncated, &playwrite[..64]).unwrap();
+        let settings = Settings {
+            monospace_font: truncated.display().to_string(),
+            ..settings
+        };
+        errors.clear();
+        let fonts = load_fonts(&settings, &mut errors);
+        assert_eq!(
+            errors,
+            [format!(
+                "{}: not a valid TrueType or OpenType font",
+                truncated.display()
+            )]
+        );
+        assert_eq!(fonts.families[&FontFamily::Monospace][0], "Hack");
+
+        // a bad fallback is reported once, not for each family
+        let settings = Settings {
+            cjk_fallback: truncated.display().to_string(),
+            ..Default::default()
+        };
+        errors.clear();
+        load_fonts(&settings, &mut errors);
+        assert_eq!(errors.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(is_font(playwrite));
+        assert!(!is_font(b"not a font"));
+    }
+}
@@ -0,0 +1,245 @@
+// keyboard shortcuts, optionally overridden by a keymap file
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+use super::{config_file, load_or_default};
+
+/// Something the user can trigger from the command palette or a shortcut.
+/// Ids are used as keys in the keymap file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Command {
+    CommandPalette,
+    /// `ToolLogic::process` of the current tool.
+    Run,
+    Clear,
+    CopyResult,
+    NextTool,
+    PreviousTool,
+    /// Sends the clipboard to the tool that fits its content.
+    SmartPaste,
+    NextTab,
+    PreviousTab,
+    CloseTab,
+    OpenSettings,
+    /// `open.<tool id>`, focuses a tab of the tool or opens one.
+    OpenTool(String),
+    /// `new.<tool id>`, opens the tool in a new tab.
+    NewTab(String),
+    /// `<tool id>.<action id>`, runs when that tool is the current one.
+    ToolAction {
+        tool: String,
+        action: String,
+    },
+}
+
+impl Command {
+    pub fn parse(id: &str) -> Option<Command> {
+        let command = match id {
+            "command_palette" => Command::CommandPalette,
+            "run" => Command::Run,
+            "clear" => Command::Clear,
+            "copy_result" => Command::CopyResult,
+            "next_tool" => Command::NextTool,
+            "previous_tool" => Command::PreviousTool,
+            "smart_paste" => Command::SmartPaste,
+            "next_tab" => Command::NextTab,
+            "previous_tab" => Command::PreviousTab,
+            "close_tab" => Command::CloseTab,
+            "settings" => Command::OpenSettings,
+            _ => match id.split_once('.')? {
+                ("open", tool) if !tool.is_empty() => Command::OpenTool(tool.to_string()),
+                ("new", tool) if !tool.is_empty() => Command::NewTab(tool.to_string()),
+                (tool, action) if !tool.is_empty() && !action.is_empty() => Command::ToolAction {
+                    tool: tool.to_string(),
+                    action: action.to_string(),
+                },
+                _ => return None,
+            },
+        };
+        Some(command)
+    }
+
+    pub fn id(&self) -> String {
+        match self {
+            Command::CommandPalette => "command_palette".to_string(),
+            Command::Run => "run".to_string(),
+            Command::Clear => "clear".to_string(),
+            Command::CopyResult => "copy_result".to_string(),
+            Command::NextTool => "next_tool".to_string(),
+            Command::PreviousTool => "previous_tool".to_string(),
+            Command::SmartPaste => "smart_paste".to_string(),
+            Command::NextTab => "next_tab".to_string(),
+            Command::PreviousTab => "previous_tab".to_string(),
+            Command::CloseTab => "close_tab".to_string(),
+            Command::OpenSettings => "settings".to_string(),
+            Command::OpenTool(tool) => format!("open.{}", tool),
+            Command::NewTab(tool) => format!("new.{}", tool),
+            Command::ToolAction { tool, action } => format!("{}.{}", tool, action),
+        }
+    }
+}
+
+/// Parses shortcuts like `Ctrl+Shift+K`. `Ctrl` and `Cmd` both mean the
+/// platform's command key, so one keymap works on every OS.
+pub fn parse_shortcut(text: &str) -> Result<KeyboardShortcut, String> {
+    let mut modifiers = Modifiers::NONE;
+    let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+    let key = parts.pop().unwrap_or_default();
+    for part in parts {
+        modifiers = modifiers
+            | match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" | "cmd" | "command" => Modifiers::COMMAND,
+                "shift" => Modifiers::SHIFT,
+                "alt" | "option" => Modifiers::ALT,
+                _ => return Err(format!("unknown modifier `{}` in `{}`", part, text)),
+            };
+    }
+    let key = Key::from_name(key)
+        .or_else(|| Key::from_name(&key.to_ascii_uppercase()))
+        .ok_or_else(|| format!("unknown key `{}` in `{}`", key, text))?;
+    Ok(KeyboardShortcut::new(modifiers, key))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(Command, KeyboardShortcut)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = [
+            (Command::CommandPalette, "Ctrl+K"),
+            (Command::Run, "Ctrl+Enter"),
+            (Command::Clear, "Ctrl+Shift+Backspace"),
+            (Command::CopyResult, "Alt+C"),
+            (Command::NextTool, "Ctrl+PageDown"),
+            (Command::PreviousTool, "Ctrl+PageUp"),
+            (Command::SmartPaste, "Alt+V"),
+            (Command::NextTab, "Ctrl+Tab"),
+            (Command::PreviousTab, "Ctrl+Shift+Tab"),
+            (Command::CloseTab, "Ctrl+W"),
+            (Command::OpenSettings, "Ctrl+Comma"),
+        ]
+        .into_iter()
+        .map(|(command, shortcut)| (command, parse_shortcut(shortcut).unwrap()))
+        .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// `keymap.json` next to the app's saved state.
+    pub fn file() -> Option<PathBuf> {
+        config_file("keymap.json")
+    }
+
+    /// Reads a keymap file, a missing file gives the defaults.
+    pub fn load(path: &Path) -> Result<Keymap, String> {
+        load_or_default(path, Self::from_json)
+    }
+
+    /// A JSON object from command id to shortcut, e.g.
+    /// `{"run": "Ctrl+R", "json.minify": "Ctrl+M"}`. Listed commands replace
+    /// their default binding, an empty shortcut removes it.
+    pub fn from_json(src: &str) -> Result<Keymap, String> {
+        let overrides: BTreeMap<String, String> =
+            serde_json::from_str(src).map_err(|e| e.to_string())?;
+        let mut keymap = Keymap::default();
+        for (id, shortcut) in overrides {
+            let command = Command::parse(&id).ok_or_else(|| format!("unknown command `{}`", id))?;
+            keymap.bindings.retain(|(bound, _)| *bound != command);
+            if !shortcut.trim().is_empty() {
+                keymap.bindings.push((command, parse_shortcut(&shortcut)?));
+            }
+        }
+        Ok(keymap)
+    }
+
+    pub fn shortcut(&self, command: &Command) -> Option<&KeyboardShortcut> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| bound == command)
+            .map(|(_, shortcut)| shortcut)
+    }
+
+    /// Consumes the first pressed shortcut. Shortcuts with more modifiers are
+    /// checked first so `Ctrl+Shift+K` is not taken for `Ctrl+K`.
+    pub fn pressed(&self, ctx: &egui::Context) -> Option<Command> {
+        let mut bindings: Vec<&(Command, KeyboardShortcut)> = self.bindings.iter().collect();
+        bindings.sort_by_key(|(_, shortcut)| {
+            let m = shortcut.modifiers;
+            std::cmp::Reverse(m.command as u8 + m.shift as u8 + m.alt as u8)
+        });
+        ctx.input_mut(|input| {
+            bindings
+                .into_iter()
+                .find(|(_, shortcut)| input.consume_shortcut(shortcut))
+                .map(|(command, _)| command.clone())
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_shortcut() {
+        assert_eq!(
+            parse_shortcut("Ctrl+Shift+k"),
+            Ok(KeyboardShortcut::new(
+                Modifiers::COMMAND | Modifiers::SHIFT,
+                Key::K
+            ))
+        );
+        assert_eq!(
+            parse_shortcut("Enter"),
+            Ok(KeyboardShortcut::new(Modifiers::NONE, Key::Enter))
+        );
+        assert!(parse_shortcut("Hyper+K").is_err());
+        assert!(parse_shortcut("Ctrl+Nope").is_err());
+    }
+
+    #[test]
+    fn test_default_bindings() {
+        // egui turns Ctrl+C, X and V into clipboard events, whatever other
+        // modifiers are held, so shortcuts on them never fire
+        for (command, shortcut) in &Keymap::default().bindings {
+            assert!(
+                !(shortcut.modifiers.command
+                    && matches!(shortcut.logical_key, Key::C | Key::X | Key::V)),
+                "{} is bound to a clipboard shortcut",
+                command.id()
+            );
+        }
+    }
+
+    #[test]
+    fn test_keymap_file() {
+        let keymap =
+            Keymap::from_json(r#"{"run": "Ctrl+R", "clear": "", "json.minify": "Alt+M"}"#).unwrap();
+        assert_eq!(
+            keymap.shortcut(&Command::Run),
+            Some(&KeyboardShortcut::new(Modifiers::COMMAND, Key::R))
+        );
+        assert_eq!(keymap.shortcut(&Command::Clear), None);
+        let minify = Command::parse("json.minify").unwrap();
+        assert_eq!(minify.id(), "json.minify");
+        assert!(keymap.shortcut(&minify).is_some());
+        // untouched commands keep their default
+        assert!(keymap.shortcut(&Command::CommandPalette).is_some());
+
+        assert_eq!(
+            Command::parse("open.json"),
+            Some(Command::OpenTool("json".to_string()))
+        );
+        assert_eq!(Command::NewTab("diff".to_string()).id(), "new.diff");
+        assert_eq!(Command::parse("settings"), Some(Command::OpenSettings));
+        assert!(Keymap::from_json(r#"{"nonsense": "Ctrl+X"}"#).is_err());
+    }
+}
@@ -1,21 +1,66 @@
-use egui::Color32;
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug)]
-pub struct AppStyle {
-    pub background: Color32,
-    pub foreground: Color32,
-    pub circle_focus: Color32,
-    pub rounds: Color32,
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+pub mod fonts;
+pub mod keymap;
+pub mod settings;
+pub mod theme;
+
+pub use settings::Settings;
+pub use theme::AppStyle;
+
+/// Name eframe stores the app's data under.
+pub const APP_ID: &str = "Developer Tools";
+
+/// `name` next to the app's saved state, `None` on systems without a place
+/// for it.
+pub fn config_file(name: &str) -> Option<PathBuf> {
+    eframe::storage_dir(APP_ID).map(|dir| dir.join(name))
+}
+
+/// Reads `path` with `parse`, a missing file gives the default. Errors name
+/// the file.
+pub fn load_or_default<T: Default>(
+    path: &Path,
+    parse: impl FnOnce(&str) -> Result<T, String>,
+) -> Result<T, String> {
+    match fs::read_to_string(path) {
+        Ok(src) => parse(&src).map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
 }
 
-impl Default for AppStyle {
-    fn default() -> Self {
-        Self {
-            background: Color32::from_hex("#2f384b").unwrap(),
-            foreground: Color32::from_hex("#f0ead6").unwrap(),
-            circle_focus: Color32::from_hex("#f25a48").unwrap(),
-            rounds: Color32::from_hex("#fceea7").unwrap(),
-        }
+/// Writes `contents` to `path`, creating its folder first.
+pub fn save_file(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    fs::write(path, contents).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_config_files() {
+        let parse = |src: &str| src.trim().parse::<u32>().map_err(|e| e.to_string());
+        assert_eq!(
+            load_or_default(Path::new("/nonexistent/number.txt"), parse),
+            Ok(0)
+        );
+
+        let dir = std::env::temp_dir().join(format!("config-files-{}", std::process::id()));
+        let path = dir.join("nested").join("number.txt");
+        save_file(&path, "42").unwrap();
+        assert_eq!(load_or_default(&path, parse), Ok(42));
+
+        save_file(&path, "forty-two").unwrap();
+        let error = load_or_default(&path, parse).unwrap_err();
+        assert!(error.starts_with(&path.display().to_string()));
+        fs::remove_dir_all(dir).unwrap();
     }
 }
@@ -0,0 +1,103 @@
+// user preferences, kept in a settings file
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{config_file, fonts, load_or_default, save_file};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Multiplies the screen's own scale, e.g. 1.5 for larger UI on a
+    /// high-DPI screen.
+    pub ui_scale: f32,
+    /// A bundled font, see [`fonts::BUNDLED_FONTS`], or the path of a font file.
+    pub body_font: String,
+    pub body_size: f32,
+    pub monospace_font: String,
+    pub monospace_size: f32,
+    /// Used for characters the other fonts lack, such as CJK. Empty for none.
+    pub cjk_fallback: String,
+    /// Size of the first window, later ones reuse the last window size.
+    pub window_size: [f32; 2],
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            body_font: fonts::HARMONYOS.to_string(),
+            body_size: 16.0,
+            monospace_font: fonts::HACK.to_string(),
+            monospace_size: 14.0,
+            cjk_fallback: fonts::HARMONYOS.to_string(),
+            window_size: [720.0, 580.0],
+        }
+    }
+}
+
+impl Settings {
+    pub const UI_SCALE: std::ops::RangeInclusive<f32> = 0.5..=3.0;
+    pub const FONT_SIZE: std::ops::RangeInclusive<f32> = 8.0..=40.0;
+    pub const MIN_WINDOW_SIZE: [f32; 2] = [480.0, 360.0];
+
+    /// `settings.toml` next to the app's saved state.
+    pub fn file() -> Option<PathBuf> {
+        config_file("settings.toml")
+    }
+
+    /// Reads a settings file, a missing file gives the defaults.
+    pub fn load(path: &Path) -> Result<Settings, String> {
+        load_or_default(path, Self::from_toml)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let src = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        save_file(path, &src)
+    }
+
+    /// Values out of range are clamped.
+    pub fn from_toml(src: &str) -> Result<Settings, String> {
+        let settings: Settings = toml::from_str(src).map_err(|e| e.message().to_string())?;
+        Ok(settings.clamped())
+    }
+
+    pub fn clamped(mut self) -> Settings {
+        let clamp = |value: f32, range: &std::ops::RangeInclusive<f32>| {
+            value.clamp(*range.start(), *range.end())
+        };
+        self.ui_scale = clamp(self.ui_scale, &Self::UI_SCALE);
+        self.body_size = clamp(self.body_size, &Self::FONT_SIZE);
+        self.monospace_size = clamp(self.monospace_size, &Self::FONT_SIZE);
+        for (size, min) in self.window_size.iter_mut().zip(Self::MIN_WINDOW_SIZE) {
+            *size = size.max(min);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_settings_file() {
+        let settings = Settings {
+            ui_scale: 1.5,
+            body_font: "/usr/share/fonts/Inter.ttf".to_string(),
+            cjk_fallback: String::new(),
+            ..Default::default()
+        };
+        let src = toml::to_string_pretty(&settings).unwrap();
+        assert_eq!(Settings::from_toml(&src), Ok(settings));
+
+        // missing values are the defaults, others are kept in range
+        let settings = Settings::from_toml("ui_scale = 10.0\nmonospace_size = 2.0").unwrap();
+        assert_eq!(settings.ui_scale, 3.0);
+        assert_eq!(settings.monospace_size, 8.0);
+        assert_eq!(settings.body_font, Settings::default().body_font);
+
+        assert!(Settings::from_toml("ui_scale = \"big\"").is_err());
+    }
+}
@@ -0,0 +1,253 @@
+// color themes, built in or read from TOML/JSON theme files
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use egui::{Color32, Visuals};
+use serde::{Deserialize, Serialize};
+
+use super::config_file;
+
+const BUILTIN_THEMES: [&str; 5] = [
+    include_str!("../../assets/themes/dark.toml"),
+    include_str!("../../assets/themes/light.toml"),
+    include_str!("../../assets/themes/midnight.toml"),
+    include_str!("../../assets/themes/solarized_dark.toml"),
+    include_str!("../../assets/themes/solarized_light.toml"),
+];
+
+/// Colors of the app and its tools. Theme files list the same fields, with
+/// colors as `#rrggbb` or `#rrggbbaa`; missing fields come from the built-in
+/// dark theme.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppStyle {
+    #[serde(default)]
+    pub name: String,
+    /// Starts from egui's dark visuals instead of the light ones.
+    pub dark: bool,
+    #[serde(with = "hex_color")]
+    pub background: Color32,
+    #[serde(with = "hex_color")]
+    pub foreground: Color32,
+    /// Selection, links and the logo.
+    #[serde(with = "hex_color")]
+    pub accent: Color32,
+    /// Secondary text such as line numbers.
+    #[serde(with = "hex_color")]
+    pub muted: Color32,
+    #[serde(with = "hex_color")]
+    pub error: Color32,
+    #[serde(with = "hex_color")]
+    pub warning: Color32,
+    #[serde(with = "hex_color")]
+    pub success: Color32,
+    #[serde(with = "hex_color")]
+    pub diff_insert: Color32,
+    #[serde(with = "hex_color")]
+    pub diff_delete: Color32,
+    /// Behind the changed words of a diff line.
+    #[serde(with = "hex_color")]
+    pub diff_insert_background: Color32,
+    #[serde(with = "hex_color")]
+    pub diff_delete_background: Color32,
+    /// One of syntect's default themes, e.g. `base16-ocean.dark` or
+    /// `InspiredGitHub`.
+    pub syntax_theme: String,
+}
+
+impl Default for AppStyle {
+    fn default() -> Self {
+        Self {
+            name: "Dark".to_string(),
+            dark: true,
+            background: Color32::from_gray(27),
+            foreground: Color32::from_gray(140),
+            accent: Color32::from_rgb(144, 125, 172),
+            muted: Color32::GRAY,
+            error: Color32::RED,
+            warning: Color32::YELLOW,
+            success: Color32::GREEN,
+            diff_insert: Color32::GREEN,
+            diff_delete: Color32::RED,
+            diff_insert_background: Color32::from_rgb(46, 90, 46),
+            diff_delete_background: Color32::from_rgb(106, 42, 42),
+            syntax_theme: "base16-mocha.dark".to_string(),
+        }
+    }
+}
+
+impl AppStyle {
+    /// The themes shipped with the app, the first one is the default.
+    pub fn builtin() -> Vec<AppStyle> {
+        BUILTIN_THEMES
+            .iter()
+            .map(|src| Self::from_toml(src).expect("built-in theme"))
+            .collect()
+    }
+
+    pub fn from_toml(src: &str) -> Result<AppStyle, String> {
+        toml::from_str(src).map_err(|e| e.message().to_string())
+    }
+
+    pub fn from_json(src: &str) -> Result<AppStyle, String> {
+        serde_json::from_str(src).map_err(|e| e.to_string())
+    }
+
+    /// Reads a `.toml` or `.json` theme file. A theme without a name is named
+    /// after its file.
+    pub fn load(path: &Path) -> Result<AppStyle, String> {
+        let src = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let style = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&src),
+            Some("json") => Self::from_json(&src),
+            _ => Err("theme files end in .toml or .json".to_string()),
+        };
+        let mut style = style.map_err(|e| format!("{}: {}", path.display(), e))?;
+        if style.name.trim().is_empty() {
+            let stem = path.file_stem().unwrap_or_default();
+            style.name = stem.to_string_lossy().to_string();
+        }
+        Ok(style)
+    }
+
+    /// `themes/` next to the app's saved state.
+    pub fn dir() -> Option<PathBuf> {
+        config_file("themes")
+    }
+
+    /// Every theme file of `dir`, sorted by name, and the errors of those
+    /// that could not be read. A missing directory has no themes.
+    pub fn load_dir(dir: &Path) -> (Vec<AppStyle>, Vec<String>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return (Vec::new(), Vec::new());
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| {
+                matches!(
+                    path.extension().and_then(|ext| ext.to_str()),
+                    Some("toml" | "json")
+                )
+            })
+            .collect();
+        paths.sort();
+
+        let (mut styles, mut errors) = (Vec::new(), Vec::new());
+        for path in paths {
+            match Self::load(&path) {
+                Ok(style) => styles.push(style),
+                Err(e) => errors.push(e),
+            }
+        }
+        (styles, errors)
+    }
+
+    fn id() -> egui::Id {
+        egui::Id::new("app_style")
+    }
+
+    /// The style last applied to `ctx`, for views to read their colors.
+    pub fn current(ctx: &egui::Context) -> Arc<AppStyle> {
+        ctx.data(|data| data.get_temp(Self::id()))
+            .unwrap_or_default()
+    }
+
+    pub fn visuals(&self) -> Visuals {
+        let mut visuals = if self.dark {
+            Visuals::dark()
+        } else {
+            Visuals::light()
+        };
+        visuals.panel_fill = self.background;
+        visuals.window_fill = self.background;
+        visuals.widgets.noninteractive.fg_stroke.color = self.foreground;
+        visuals.hyperlink_color = self.accent;
+        visuals.selection.bg_fill = self.accent.gamma_multiply(0.6);
+        visuals.error_fg_color = self.error;
+        visuals.warn_fg_color = self.warning;
+        visuals
+    }
+
+    /// Switches egui to this style and makes it the [`AppStyle::current`] one.
+    pub fn apply(&self, ctx: &egui::Context) {
+        let theme = if self.dark {
+            egui::Theme::Dark
+        } else {
+            egui::Theme::Light
+        };
+        ctx.set_theme(theme);
+        ctx.set_visuals_of(theme, self.visuals());
+        ctx.data_mut(|data| data.insert_temp(Self::id(), Arc::new(self.clone())));
+    }
+}
+
+/// Colors as hex strings, the alpha is only written when not opaque.
+mod hex_color {
+    use egui::Color32;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color32, serializer: S) -> Result<S::Ok, S::Error> {
+        let [r, g, b, a] = color.to_srgba_unmultiplied();
+        let hex = if a == u8::MAX {
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+        };
+        serializer.serialize_str(&hex)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color32, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        Color32::from_hex(&hex)
+            .map_err(|_| serde::de::Error::custom(format!("invalid color `{}`", hex)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_builtin_themes() {
+        let themes = AppStyle::builtin();
+        assert_eq!(themes.len(), BUILTIN_THEMES.len());
+        // the default is the first built-in theme
+        assert_eq!(themes[0], AppStyle::default());
+        assert!(themes.iter().any(|theme| !theme.dark));
+        for theme in &themes {
+            let json = serde_json::to_string(theme).unwrap();
+            assert_eq!(&AppStyle::from_json(&json).unwrap(), theme);
+        }
+    }
+
+    #[test]
+    fn test_theme_file() {
+        let style = AppStyle::from_toml(
+            r##"
+            name = "Mine"
+            accent = "#ff8800"
+            diff_insert_background = "#00ff0040"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(style.name, "Mine");
+        assert_eq!(style.accent, Color32::from_rgb(255, 136, 0));
+        assert_eq!(
+            style.diff_insert_background,
+            Color32::from_rgba_unmultiplied(0, 255, 0, 64)
+        );
+        // everything else is the default
+        assert_eq!(style.background, AppStyle::default().background);
+
+        assert!(AppStyle::from_toml(r##"accent = "orange""##).is_err());
+        assert!(AppStyle::from_json(r#"{"dark": "yes"}"#).is_err());
+        assert_eq!(
+            AppStyle::load_dir(Path::new("/nonexistent/themes")),
+            (Vec::new(), Vec::new())
+        );
+    }
+}
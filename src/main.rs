@@ -1,19 +1,39 @@
 mod app;
-use std::{collections::BTreeMap, sync::Arc};
+use std::{io::Read, path::Path};
 
 use app::App;
-use egui::{FontData, FontDefinitions, FontFamily, FontId, TextStyle};
-
-pub const PLAY_WRITE_FAMILY_NAME: &str = "Playwrite AU SA";
-pub const HARMONYOS_FAMILY_NAME: &str = "HarmonyOS SANS SC";
-const ALIBABAPUHUITI_FAMILY_NAME: &str = "AlibabaPuHuiTi-3-55-Regular";
+use developer_tools::config::{Settings, APP_ID};
+use developer_tools::tools::{Pipeline, ToolRegistry};
 
 fn main() -> eframe::Result<()> {
+    // `--pipeline <name or file.json>` runs a pipeline on stdin without the UI
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let [flag, pipeline] = &args[..] {
+        if flag == "--pipeline" {
+            match run_pipeline(pipeline) {
+                Ok(output) => println!("{}", output),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+    }
+
     const ICON: &[u8] = include_bytes!("../assets/logo.png");
 
+    // read before the window opens, it has the window size
+    let (settings, settings_error) = match Settings::file().map(|path| Settings::load(&path)) {
+        Some(Ok(settings)) => (settings, None),
+        Some(Err(e)) => (Settings::default(), Some(e)),
+        None => (Settings::default(), None),
+    };
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size([720.0, 580.0])
+            .with_inner_size(settings.window_size)
+            .with_min_inner_size(Settings::MIN_WINDOW_SIZE)
             .with_title("Developer Tools")
             .with_taskbar(true)
             .with_icon(eframe::icon_data::from_png_bytes(ICON).unwrap()),
@@ -21,105 +41,37 @@ fn main() -> eframe::Result<()> {
     };
 
     eframe::run_native(
-        "Developer Tools",
+        APP_ID,
         native_options,
         Box::new(|cc| {
-            setup_fonts(&cc.egui_ctx);
             egui_extras::install_image_loaders(&cc.egui_ctx);
-            Ok(Box::new(App::new(cc)))
+            Ok(Box::new(App::new(cc, settings, settings_error)))
         }),
     )
 }
 
-#[inline]
-fn logo() -> TextStyle {
-    TextStyle::Name("logo".into())
-}
-
-fn setup_fonts(ctx: &egui::Context) {
-    load_fonts(ctx);
-
-    let text_styles: BTreeMap<_, _> = [
-        (
-            egui::TextStyle::Heading,
-            FontId::new(20.0, FontFamily::Proportional),
-        ),
-        (
-            logo(),
-            FontId::new(25.0, FontFamily::Name(PLAY_WRITE_FAMILY_NAME.into())),
-        ),
-        (
-            egui::TextStyle::Name("SidebarHeader".into()),
-            FontId::new(25.0, FontFamily::Name(PLAY_WRITE_FAMILY_NAME.into())),
-        ),
-        (
-            egui::TextStyle::Name("Heading2".into()),
-            FontId::new(25.0, FontFamily::Proportional),
-        ),
-        (
-            egui::TextStyle::Name("Context".into()),
-            FontId::new(23.0, FontFamily::Proportional),
-        ),
-        (
-            egui::TextStyle::Body,
-            FontId::new(16.0, FontFamily::Proportional),
-        ),
-        (
-            egui::TextStyle::Monospace,
-            FontId::new(14.0, FontFamily::Monospace),
-        ),
-        (
-            egui::TextStyle::Button,
-            FontId::new(14.0, FontFamily::Monospace),
-        ),
-        (
-            egui::TextStyle::Small,
-            FontId::new(10.0, FontFamily::Proportional),
-        ),
-    ]
-    .into();
-
-    ctx.all_styles_mut(move |style| style.text_styles = text_styles.clone());
-}
-
-fn load_fonts(ctx: &egui::Context) {
-    let msyh_font = FontData::from_static(include_bytes!(
-        "../assets/fonts/HarmonyOS_SansSC_Regular.ttf"
-    ));
-    let handwriting_font = FontData::from_static(include_bytes!("../assets/fonts/Playwrite.ttf"));
-
-    let puhu_font = FontData::from_static(include_bytes!(
-        "../assets/fonts/AlibabaPuHuiTi-3-55-Regular.ttf"
-    ));
-
-    let mut fonts = FontDefinitions::default();
-
-    fonts
-        .font_data
-        .insert(HARMONYOS_FAMILY_NAME.to_owned(), Arc::from(msyh_font));
-    fonts.font_data.insert(
-        PLAY_WRITE_FAMILY_NAME.to_owned(),
-        Arc::from(handwriting_font),
-    );
-
-    fonts
-        .font_data
-        .insert(ALIBABAPUHUITI_FAMILY_NAME.to_owned(), Arc::from(puhu_font));
-
-    fonts.families.insert(
-        FontFamily::Proportional,
-        vec![HARMONYOS_FAMILY_NAME.to_owned()],
-    );
-
-    // fonts.families.insert(
-    //     FontFamily::Monospace,
-    //     vec![ALIBABAPUHUITI_FAMILY_NAME.to_owned()],
-    // );
-
-    fonts.families.insert(
-        FontFamily::Name(PLAY_WRITE_FAMILY_NAME.into()),
-        vec![PLAY_WRITE_FAMILY_NAME.to_owned()],
-    );
+/// Runs a pipeline file, or a pipeline saved from the app by name, on stdin.
+fn run_pipeline(name: &str) -> Result<String, String> {
+    let path = Path::new(name);
+    let pipeline = if path.is_file() {
+        let src = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", name, e))?;
+        serde_json::from_str(&src).map_err(|e| format!("{}: {}", name, e))?
+    } else {
+        let saved = match Pipeline::file() {
+            Some(file) => Pipeline::load_all(&file)?,
+            None => Vec::new(),
+        };
+        saved
+            .into_iter()
+            .find(|pipeline| pipeline.name == name)
+            .ok_or_else(|| format!("no pipeline file or saved pipeline named {}", name))?
+    };
 
-    ctx.set_fonts(fonts);
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|e| e.to_string())?;
+    pipeline
+        .apply(&ToolRegistry::builtin(), &input)
+        .map_err(|e| e.to_string())
 }
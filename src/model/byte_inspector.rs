@@ -0,0 +1,79 @@
+// helpers for looking at raw bytes
+
+/// Known file signatures, checked in order.
+const SIGNATURES: [(&[u8], &str); 9] = [
+    (b"\x89PNG\r\n\x1a\n", "PNG image"),
+    (b"\xff\xd8\xff", "JPEG image"),
+    (b"GIF87a", "GIF image"),
+    (b"GIF89a", "GIF image"),
+    (b"%PDF-", "PDF document"),
+    (b"\x1f\x8b", "gzip archive"),
+    (b"PK\x03\x04", "ZIP archive"),
+    (b"PK\x05\x06", "ZIP archive (empty)"),
+    (b"7z\xbc\xaf\x27\x1c", "7-Zip archive"),
+];
+
+/// Names the file type from its magic number.
+pub fn detect_magic(bytes: &[u8]) -> Option<&'static str> {
+    SIGNATURES
+        .iter()
+        .find(|(magic, _)| bytes.starts_with(magic))
+        .map(|(_, name)| *name)
+}
+
+/// `hexdump -C` style dump of at most `limit` bytes.
+pub fn hex_dump(bytes: &[u8], limit: usize) -> String {
+    let mut dump = String::new();
+    for (row, chunk) in bytes[..bytes.len().min(limit)].chunks(16).enumerate() {
+        dump.push_str(&format!("{:08x} ", row * 16));
+        for column in 0..16 {
+            if column == 8 {
+                dump.push(' ');
+            }
+            match chunk.get(column) {
+                Some(byte) => dump.push_str(&format!(" {:02x}", byte)),
+                None => dump.push_str("   "),
+            }
+        }
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        dump.push_str(&format!("  |{}|\n", ascii));
+    }
+    if bytes.len() > limit {
+        dump.push_str(&format!("... {} more bytes\n", bytes.len() - limit));
+    }
+    dump
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_detect_magic() {
+        assert_eq!(detect_magic(b"\x89PNG\r\n\x1a\n\0\0"), Some("PNG image"));
+        assert_eq!(detect_magic(b"\x1f\x8b\x08"), Some("gzip archive"));
+        assert_eq!(detect_magic(b"%PDF-1.7"), Some("PDF document"));
+        assert_eq!(detect_magic(b"PK\x03\x04"), Some("ZIP archive"));
+        assert_eq!(detect_magic(b"plain"), None);
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        let dump = hex_dump(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR!", 64);
+        assert_eq!(
+            dump,
+            "00000000  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52  |.PNG........IHDR|\n\
+             00000010  21                                                |!|\n"
+        );
+        assert!(hex_dump(&[0u8; 40], 16).ends_with("... 24 more bytes\n"));
+    }
+}
@@ -0,0 +1,216 @@
+// guesses what a pasted text is
+
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine as _,
+};
+
+use super::{CronExpression, DateFormat, Jwt, Zone};
+
+const SQL_KEYWORDS: [&str; 10] = [
+    "SELECT", "INSERT", "UPDATE", "DELETE", "CREATE", "ALTER", "DROP", "WITH", "MERGE", "TRUNCATE",
+];
+
+const LENIENT: GeneralPurposeConfig = GeneralPurposeConfig::new()
+    .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+    .with_decode_allow_trailing_bits(true);
+const BASE64_STANDARD: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, LENIENT);
+const BASE64_URL: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, LENIENT);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentKind {
+    Json,
+    Jwt,
+    Sql,
+    /// Unix time in seconds, milliseconds, microseconds or nanoseconds.
+    Timestamp,
+    DateTime,
+    Cron,
+    Base64,
+    Hex,
+    Number,
+    Diff,
+    /// Anything else, which can at least be hashed.
+    Text,
+}
+
+impl ContentKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContentKind::Json => "JSON",
+            ContentKind::Jwt => "JWT",
+            ContentKind::Sql => "SQL statement",
+            ContentKind::Timestamp => "Unix timestamp",
+            ContentKind::DateTime => "Date and time",
+            ContentKind::Cron => "Cron expression",
+            ContentKind::Base64 => "Base64",
+            ContentKind::Hex => "Hexadecimal number",
+            ContentKind::Number => "Number",
+            ContentKind::Diff => "Unified diff",
+            ContentKind::Text => "Text",
+        }
+    }
+
+    /// Registry id of the tool that handles this kind of content.
+    pub fn tool_id(&self) -> &'static str {
+        match self {
+            ContentKind::Json => "json",
+            ContentKind::Jwt => "jwt",
+            ContentKind::Sql => "sql",
+            ContentKind::Timestamp | ContentKind::DateTime => "datetime",
+            ContentKind::Cron => "cron",
+            ContentKind::Base64 => "base64",
+            ContentKind::Hex | ContentKind::Number => "number-base",
+            ContentKind::Diff => "patch",
+            ContentKind::Text => "hash",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentMatch {
+    pub kind: ContentKind,
+    /// 0 to 100.
+    pub confidence: u8,
+}
+
+/// Every kind `text` could be, most likely first. Non-empty text always
+/// ends with [`ContentKind::Text`].
+pub fn detect_content(text: &str) -> Vec<ContentMatch> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let single_line = !text.contains('\n');
+    let mut matches = Vec::new();
+    let mut push = |kind, confidence| matches.push(ContentMatch { kind, confidence });
+
+    if single_line && text.matches('.').count() == 2 && Jwt::decode(text).is_ok() {
+        push(ContentKind::Jwt, 98);
+    }
+    if text.starts_with('{') || text.starts_with('[') {
+        let valid = serde_json::from_str::<serde_json::Value>(text).is_ok();
+        push(ContentKind::Json, if valid { 95 } else { 40 });
+    }
+    if text.starts_with("diff --git")
+        || (text.starts_with("--- ") && text.contains("\n+++ "))
+        || text.starts_with("@@ -")
+    {
+        push(ContentKind::Diff, 90);
+    }
+    let first_word = text
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or_default();
+    if SQL_KEYWORDS
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(first_word))
+    {
+        push(ContentKind::Sql, 85);
+    }
+
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    let numeric = !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit());
+    if single_line && DateFormat::detect(text, Zone::UTC).is_some() {
+        if !numeric {
+            push(ContentKind::DateTime, 85);
+        } else if (9..=19).contains(&digits.len()) {
+            push(ContentKind::Timestamp, 80);
+        }
+    }
+    let fields = text.split_whitespace().count();
+    if single_line && !numeric && (5..=7).contains(&fields) && CronExpression::parse(text).is_ok() {
+        push(ContentKind::Cron, 80);
+    }
+
+    let hex = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X"));
+    let is_hex = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit());
+    match hex {
+        Some(hex) if is_hex(hex) => push(ContentKind::Hex, 90),
+        None if !numeric && is_hex(text) => push(ContentKind::Hex, 65),
+        _ => {}
+    }
+    if numeric {
+        push(ContentKind::Number, 60);
+    }
+
+    if !numeric && !is_hex(text) {
+        if let Some(decoded) = decode_base64(text) {
+            let readable = std::str::from_utf8(&decoded)
+                .is_ok_and(|s| s.chars().all(|c| !c.is_control() || c.is_whitespace()));
+            push(ContentKind::Base64, if readable { 75 } else { 45 });
+        }
+    }
+
+    push(ContentKind::Text, 10);
+    matches.sort_by_key(|m| std::cmp::Reverse(m.confidence));
+    matches
+}
+
+/// Strict enough that ordinary words are not taken for base64: only the
+/// base64 alphabets, optionally wrapped over lines, and at least 8 characters.
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let compact: String = text.split_whitespace().collect();
+    let alphabet = |c: char| c.is_ascii_alphanumeric() || "+/-_=".contains(c);
+    if compact.len() < 8 || !compact.chars().all(alphabet) || text.contains(' ') {
+        return None;
+    }
+    BASE64_STANDARD
+        .decode(&compact)
+        .or_else(|_| BASE64_URL.decode(&compact))
+        .ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn best(text: &str) -> ContentKind {
+        detect_content(text)[0].kind
+    }
+
+    #[test]
+    fn test_detect_content() {
+        assert_eq!(best(r#"{"a": [1, 2]}"#), ContentKind::Json);
+        assert_eq!(best("select * from users where id = 1"), ContentKind::Sql);
+        assert_eq!(best("1700000000"), ContentKind::Timestamp);
+        assert_eq!(best("2024-03-01T12:00:00Z"), ContentKind::DateTime);
+        assert_eq!(best("*/15 9-17 * * MON-FRI"), ContentKind::Cron);
+        assert_eq!(best("aGVsbG8gd29ybGQ="), ContentKind::Base64);
+        assert_eq!(best("0xff00"), ContentKind::Hex);
+        assert_eq!(best("42"), ContentKind::Number);
+        assert_eq!(
+            best("--- a/x\n+++ b/x\n@@ -1 +1 @@\n-a\n+b\n"),
+            ContentKind::Diff
+        );
+        assert_eq!(
+            best("eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIn0.c2ln"),
+            ContentKind::Jwt
+        );
+        assert_eq!(best("just some words"), ContentKind::Text);
+        assert!(detect_content("   ").is_empty());
+    }
+
+    #[test]
+    fn test_ambiguous_content() {
+        // an epoch is also a plain number, hex digits are not taken for base64
+        let kinds: Vec<ContentKind> = detect_content("1700000000")
+            .iter()
+            .map(|m| m.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ContentKind::Timestamp,
+                ContentKind::Number,
+                ContentKind::Text
+            ]
+        );
+        assert_eq!(best("deadbeef"), ContentKind::Hex);
+        assert_eq!(
+            detect_content("deadbeef").last().unwrap().kind,
+            ContentKind::Text
+        );
+    }
+}
@@ -0,0 +1,787 @@
+// cron expression parser
+
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, Timelike, Utc};
+
+use super::Zone;
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const DAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+const MONTH_LABELS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const DAY_LABELS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+const ORDINALS: [&str; 5] = ["first", "second", "third", "fourth", "fifth"];
+
+/// Give up searching for fire times this many years after the start.
+const SEARCH_YEARS: i32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CronDialect {
+    /// `min hour dom month dow`, as used by crontab and Kubernetes CronJobs.
+    Standard,
+    /// `sec min hour dom month dow`, as used by Spring and many schedulers.
+    Seconds,
+    /// Quartz: seconds first, optional year, `?`/`L`/`W`/`#` and 1-based weekdays.
+    Quartz,
+}
+
+impl fmt::Display for CronDialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CronDialect::Standard => write!(f, "Standard (5 fields)"),
+            CronDialect::Seconds => write!(f, "With seconds (6 fields)"),
+            CronDialect::Quartz => write!(f, "Quartz"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CronFieldKind {
+    Second,
+    Minute,
+    Hour,
+    DayOfMonth,
+    Month,
+    DayOfWeek,
+    Year,
+}
+
+impl CronFieldKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CronFieldKind::Second => "Second",
+            CronFieldKind::Minute => "Minute",
+            CronFieldKind::Hour => "Hour",
+            CronFieldKind::DayOfMonth => "Day of month",
+            CronFieldKind::Month => "Month",
+            CronFieldKind::DayOfWeek => "Day of week",
+            CronFieldKind::Year => "Year",
+        }
+    }
+
+    /// Inclusive range of values, weekdays normalized to 0 = Sunday.
+    fn range(&self) -> (u32, u32) {
+        match self {
+            CronFieldKind::Second | CronFieldKind::Minute => (0, 59),
+            CronFieldKind::Hour => (0, 23),
+            CronFieldKind::DayOfMonth => (1, 31),
+            CronFieldKind::Month => (1, 12),
+            CronFieldKind::DayOfWeek => (0, 6),
+            CronFieldKind::Year => (1970, 2099),
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self {
+            CronFieldKind::Second => "second",
+            CronFieldKind::Minute => "minute",
+            CronFieldKind::Hour => "hour",
+            CronFieldKind::DayOfMonth => "day",
+            CronFieldKind::Month => "month",
+            CronFieldKind::DayOfWeek => "day of the week",
+            CronFieldKind::Year => "year",
+        }
+    }
+
+    fn label(&self, value: u32) -> String {
+        match self {
+            CronFieldKind::Month => MONTH_LABELS[(value - 1) as usize].to_string(),
+            CronFieldKind::DayOfWeek => DAY_LABELS[(value % 7) as usize].to_string(),
+            CronFieldKind::Hour => format!("{:02}:00", value),
+            _ => value.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CronPart {
+    /// `*` or `?`
+    Any,
+    Value(u32),
+    Range(u32, u32),
+    Step {
+        start: u32,
+        end: u32,
+        step: u32,
+    },
+    /// `L` or `L-3` in day of month
+    LastDay(u32),
+    /// `LW` in day of month
+    LastWeekday,
+    /// `15W` in day of month
+    NearestWeekday(u32),
+    /// `5L` in day of week: last Friday of the month
+    LastOf(u32),
+    /// `1#2` in day of week: second Monday of the month
+    Nth(u32, u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronField {
+    pub kind: CronFieldKind,
+    pub source: String,
+    parts: Vec<CronPart>,
+}
+
+impl CronField {
+    fn parse(kind: CronFieldKind, source: &str, dialect: CronDialect) -> Result<CronField, String> {
+        let parts = source
+            .split(',')
+            .map(|item| parse_part(kind, item.trim(), dialect))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("{} field '{}': {}", kind.name(), source, e))?;
+
+        Ok(CronField {
+            kind,
+            source: source.to_string(),
+            parts,
+        })
+    }
+
+    fn fixed(kind: CronFieldKind, value: u32) -> CronField {
+        CronField {
+            kind,
+            source: value.to_string(),
+            parts: vec![CronPart::Value(value)],
+        }
+    }
+
+    pub fn is_any(&self) -> bool {
+        self.parts.contains(&CronPart::Any)
+    }
+
+    fn single(&self) -> Option<u32> {
+        match self.parts.as_slice() {
+            [CronPart::Value(v)] => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        let sunday = self.kind == CronFieldKind::DayOfWeek && value == 0;
+        self.matches_value(value) || (sunday && self.matches_value(7))
+    }
+
+    fn matches_value(&self, value: u32) -> bool {
+        self.parts.iter().any(|part| match *part {
+            CronPart::Any => true,
+            CronPart::Value(v) => v == value,
+            CronPart::Range(from, to) => (from..=to).contains(&value),
+            CronPart::Step { start, end, step } => {
+                (start..=end).contains(&value) && (value - start).is_multiple_of(step)
+            }
+            _ => false,
+        })
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        let value = match self.kind {
+            CronFieldKind::DayOfWeek => date.weekday().num_days_from_sunday(),
+            _ => date.day(),
+        };
+        let last = last_day_of_month(date);
+
+        self.matches(value)
+            || self.parts.iter().any(|part| match *part {
+                CronPart::LastDay(offset) => last.day().checked_sub(offset) == Some(date.day()),
+                CronPart::LastWeekday => nearest_weekday(last, last.day()) == date,
+                CronPart::NearestWeekday(day) => {
+                    day <= last.day() && nearest_weekday(date, day) == date
+                }
+                CronPart::LastOf(weekday) => value == weekday && date.day() + 7 > last.day(),
+                CronPart::Nth(weekday, nth) => value == weekday && (date.day() - 1) / 7 + 1 == nth,
+                _ => false,
+            })
+    }
+
+    /// Comma separated values this field expands to, for display.
+    pub fn expanded(&self) -> String {
+        if self.is_any() {
+            return String::from("*");
+        }
+        let (min, max) = self.kind.range();
+        let values: Vec<String> = (min..=max)
+            .filter(|v| self.matches(*v))
+            .map(|v| v.to_string())
+            .collect();
+        let specials = self
+            .parts
+            .iter()
+            .filter(|p| {
+                !matches!(
+                    p,
+                    CronPart::Value(_) | CronPart::Range(..) | CronPart::Step { .. }
+                )
+            })
+            .count();
+        let mut out = values.join(",");
+        if specials > 0 {
+            if !out.is_empty() {
+                out.push(',');
+            }
+            out.push_str(&self.source);
+        }
+        out
+    }
+
+    fn describe(&self) -> String {
+        let kind = self.kind;
+        let unit = kind.unit();
+        let phrases: Vec<String> = self
+            .parts
+            .iter()
+            .map(|part| match *part {
+                CronPart::Any => format!("every {}", unit),
+                CronPart::Value(v) => match kind {
+                    CronFieldKind::Month => format!("in {}", kind.label(v)),
+                    CronFieldKind::DayOfWeek => format!("on {}", kind.label(v)),
+                    CronFieldKind::DayOfMonth => format!("on day {} of the month", v),
+                    CronFieldKind::Year => format!("in {}", v),
+                    _ => format!("at {} {}", unit, v),
+                },
+                CronPart::Range(from, to) => match kind {
+                    CronFieldKind::Month => {
+                        format!("{} through {}", kind.label(from), kind.label(to))
+                    }
+                    CronFieldKind::DayOfWeek => {
+                        format!("on {} through {}", kind.label(from), kind.label(to))
+                    }
+                    CronFieldKind::DayOfMonth => {
+                        format!("between day {} and {} of the month", from, to)
+                    }
+                    _ => format!("every {} from {} through {}", unit, from, to),
+                },
+                CronPart::Step { start, end, step } => {
+                    let (min, max) = kind.range();
+                    let every = format!("every {} {}s", step, unit);
+                    match (start == min, end == max) {
+                        (true, true) => every,
+                        (false, true) => format!("{}, starting at {} {}", every, unit, start),
+                        _ => format!("{}, {} through {}", every, start, end),
+                    }
+                }
+                CronPart::LastDay(0) => String::from("on the last day of the month"),
+                CronPart::LastDay(offset) => {
+                    format!("{} days before the last day of the month", offset)
+                }
+                CronPart::LastWeekday => String::from("on the last weekday of the month"),
+                CronPart::NearestWeekday(day) => {
+                    format!("on the weekday nearest day {} of the month", day)
+                }
+                CronPart::LastOf(weekday) => {
+                    format!("on the last {} of the month", kind.label(weekday))
+                }
+                CronPart::Nth(weekday, nth) => format!(
+                    "on the {} {} of the month",
+                    ORDINALS[(nth - 1) as usize],
+                    kind.label(weekday)
+                ),
+            })
+            .collect();
+        join_phrases(&phrases)
+    }
+}
+
+/// A parsed cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpression {
+    pub dialect: CronDialect,
+    pub second: CronField,
+    pub minute: CronField,
+    pub hour: CronField,
+    pub day_of_month: CronField,
+    pub month: CronField,
+    pub day_of_week: CronField,
+    pub year: Option<CronField>,
+}
+
+impl CronExpression {
+    /// Parses 5-field crontab, 6-field (seconds first) and Quartz expressions,
+    /// plus the `@daily`-style macros.
+    pub fn parse(input: &str) -> Result<CronExpression, String> {
+        let input = input.trim();
+        let expanded = match input.to_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other if other.starts_with('@') => {
+                return Err(format!("Unsupported macro '{}'", input));
+            }
+            _ => input,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let quartz_syntax = fields
+            .iter()
+            .any(|f| f.contains(['?', 'L', 'W', '#', 'l', 'w']));
+        let dialect = match fields.len() {
+            5 => CronDialect::Standard,
+            6 if quartz_syntax => CronDialect::Quartz,
+            6 => CronDialect::Seconds,
+            7 => CronDialect::Quartz,
+            n => {
+                return Err(format!(
+                    "Expected 5, 6 or 7 fields but found {}: min hour day month weekday",
+                    n
+                ))
+            }
+        };
+
+        let offset = if dialect == CronDialect::Standard {
+            0
+        } else {
+            1
+        };
+        let field = |kind, index: usize| CronField::parse(kind, fields[index + offset], dialect);
+
+        let expression = CronExpression {
+            dialect,
+            second: if offset == 0 {
+                CronField::fixed(CronFieldKind::Second, 0)
+            } else {
+                CronField::parse(CronFieldKind::Second, fields[0], dialect)?
+            },
+            minute: field(CronFieldKind::Minute, 0)?,
+            hour: field(CronFieldKind::Hour, 1)?,
+            day_of_month: field(CronFieldKind::DayOfMonth, 2)?,
+            month: field(CronFieldKind::Month, 3)?,
+            day_of_week: field(CronFieldKind::DayOfWeek, 4)?,
+            year: match fields.get(6) {
+                Some(year) => Some(CronField::parse(CronFieldKind::Year, year, dialect)?),
+                None => None,
+            },
+        };
+
+        if dialect == CronDialect::Quartz
+            && !(expression.day_of_month.source == "?" || expression.day_of_week.source == "?")
+        {
+            return Err(String::from(
+                "Quartz expressions need '?' in either day of month or day of week",
+            ));
+        }
+
+        Ok(expression)
+    }
+
+    pub fn fields(&self) -> Vec<&CronField> {
+        let mut fields = vec![
+            &self.second,
+            &self.minute,
+            &self.hour,
+            &self.day_of_month,
+            &self.month,
+            &self.day_of_week,
+        ];
+        if self.dialect == CronDialect::Standard {
+            fields.remove(0);
+        }
+        fields.extend(self.year.as_ref());
+        fields
+    }
+
+    /// Plain English description, e.g. `At 09:30, on Monday through Friday`.
+    pub fn explain(&self) -> String {
+        let mut sentence = Vec::new();
+
+        let second = self.second.single();
+        match (second, self.minute.single(), self.hour.single()) {
+            (Some(0), Some(m), Some(h)) => sentence.push(format!("At {:02}:{:02}", h, m)),
+            (Some(s), Some(m), Some(h)) => sentence.push(format!("At {:02}:{:02}:{:02}", h, m, s)),
+            _ => {
+                if second != Some(0) {
+                    sentence.push(self.second.describe());
+                }
+                sentence.push(self.minute.describe());
+                if !self.hour.is_any() {
+                    sentence.push(match self.hour.single() {
+                        Some(h) => format!("past hour {}", h),
+                        None => self.hour.describe(),
+                    });
+                }
+            }
+        }
+
+        if !self.day_of_month.is_any() {
+            sentence.push(self.day_of_month.describe());
+        }
+        if !self.day_of_week.is_any() {
+            let joiner = if self.day_of_month.is_any() {
+                ""
+            } else {
+                "or "
+            };
+            sentence.push(format!("{}{}", joiner, self.day_of_week.describe()));
+        }
+        if !self.month.is_any() {
+            sentence.push(self.month.describe());
+        }
+        if let Some(year) = self.year.as_ref().filter(|y| !y.is_any()) {
+            sentence.push(year.describe());
+        }
+
+        let mut text = sentence.join(", ");
+        if let Some(first) = text.get_mut(0..1) {
+            first.make_ascii_uppercase();
+        }
+        text
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let dom = &self.day_of_month;
+        let dow = &self.day_of_week;
+        match (dom.is_any(), dow.is_any()) {
+            (true, true) => true,
+            (false, true) => dom.matches_date(date),
+            (true, false) => dow.matches_date(date),
+            // classic cron: when both are restricted either may match
+            (false, false) => dom.matches_date(date) || dow.matches_date(date),
+        }
+    }
+
+    /// The next `count` fire times strictly after `after`, evaluated on the
+    /// wall clock of `zone`. Local times skipped by DST never fire, repeated
+    /// ones fire once.
+    pub fn upcoming(
+        &self,
+        after: DateTime<Utc>,
+        zone: Zone,
+        count: usize,
+    ) -> Vec<DateTime<FixedOffset>> {
+        let mut result = Vec::new();
+        let start = zone.from_utc(after).naive_local();
+        let limit = start.year() + SEARCH_YEARS;
+        let mut t = start.with_nanosecond(0).unwrap_or(start) + Duration::seconds(1);
+
+        while result.len() < count && t.year() <= limit {
+            if let Some(year) = &self.year {
+                if !year.matches(t.year() as u32) {
+                    t = start_of(t.year() + 1, 1, 1);
+                    continue;
+                }
+            }
+            if !self.month.matches(t.month()) {
+                t = match t.month() {
+                    12 => start_of(t.year() + 1, 1, 1),
+                    m => start_of(t.year(), m + 1, 1),
+                };
+                continue;
+            }
+            if !self.matches_day(t.date()) {
+                t = (t.date() + Duration::days(1))
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap_or(t);
+                continue;
+            }
+            if !self.hour.matches(t.hour()) {
+                t = t.with_minute(0).and_then(|t| t.with_second(0)).unwrap_or(t)
+                    + Duration::hours(1);
+                continue;
+            }
+            if !self.minute.matches(t.minute()) {
+                t = t.with_second(0).unwrap_or(t) + Duration::minutes(1);
+                continue;
+            }
+            if !self.second.matches(t.second()) {
+                t += Duration::seconds(1);
+                continue;
+            }
+
+            if let Ok(zoned) = zone.from_local(t) {
+                if zoned.datetime.naive_local() == t {
+                    result.push(zoned.datetime);
+                }
+            }
+            t += Duration::seconds(1);
+        }
+
+        result
+    }
+}
+
+fn parse_part(kind: CronFieldKind, item: &str, dialect: CronDialect) -> Result<CronPart, String> {
+    let upper = item.to_uppercase();
+    let (min, max) = kind.range();
+
+    if upper == "*" || (upper == "?" && is_day_kind(kind)) {
+        return Ok(CronPart::Any);
+    }
+
+    if kind == CronFieldKind::DayOfMonth {
+        if upper == "LW" {
+            return Ok(CronPart::LastWeekday);
+        }
+        if let Some(rest) = upper.strip_prefix('L') {
+            let offset = match rest.strip_prefix('-') {
+                Some(n) => parse_number(n)?,
+                None if rest.is_empty() => 0,
+                None => return Err(format!("invalid 'L' expression '{}'", item)),
+            };
+            return if offset < 31 {
+                Ok(CronPart::LastDay(offset))
+            } else {
+                Err(format!("offset {} is out of range 0-30", offset))
+            };
+        }
+        if let Some(day) = upper.strip_suffix('W') {
+            return Ok(CronPart::NearestWeekday(parse_value(kind, day, dialect)?));
+        }
+    }
+
+    if kind == CronFieldKind::DayOfWeek {
+        if upper == "L" {
+            return Ok(CronPart::Value(6));
+        }
+        if let Some(day) = upper.strip_suffix('L') {
+            return Ok(CronPart::LastOf(parse_value(kind, day, dialect)?));
+        }
+        if let Some((day, nth)) = upper.split_once('#') {
+            let nth = parse_number(nth)?;
+            if !(1..=5).contains(&nth) {
+                return Err(format!("'#{}' must be between 1 and 5", nth));
+            }
+            return Ok(CronPart::Nth(parse_value(kind, day, dialect)?, nth));
+        }
+    }
+
+    if let Some((base, step)) = upper.split_once('/') {
+        let step = parse_number(step)?;
+        if step == 0 {
+            return Err(String::from("step must be greater than 0"));
+        }
+        let (start, end) = match base {
+            "*" => (min, max),
+            _ => match base.split_once('-') {
+                Some((from, to)) => (
+                    parse_value(kind, from, dialect)?,
+                    parse_value(kind, to, dialect)?,
+                ),
+                None => (parse_value(kind, base, dialect)?, max),
+            },
+        };
+        if start > end {
+            return Err(format!("range {} is reversed", base));
+        }
+        return Ok(CronPart::Step { start, end, step });
+    }
+
+    if let Some((from, to)) = upper.split_once('-') {
+        let from = parse_value(kind, from, dialect)?;
+        let to = parse_value(kind, to, dialect)?;
+        // Sunday is normalized to 0, keep it as 7 when it closes a range like `FRI-SUN`
+        let to = if kind == CronFieldKind::DayOfWeek && to == 0 && from > 0 {
+            7
+        } else {
+            to
+        };
+        if from > to {
+            return Err(format!("range {}-{} is reversed", from, to));
+        }
+        return Ok(CronPart::Range(from, to));
+    }
+
+    parse_value(kind, &upper, dialect).map(CronPart::Value)
+}
+
+fn parse_value(kind: CronFieldKind, text: &str, dialect: CronDialect) -> Result<u32, String> {
+    let names: &[&str] = match kind {
+        CronFieldKind::Month => &MONTH_NAMES,
+        CronFieldKind::DayOfWeek => &DAY_NAMES,
+        _ => &[],
+    };
+    if let Some(index) = names.iter().position(|n| n.eq_ignore_ascii_case(text)) {
+        return Ok(index as u32 + if kind == CronFieldKind::Month { 1 } else { 0 });
+    }
+
+    let value = parse_number(text)?;
+    let (min, max) = match (kind, dialect) {
+        (CronFieldKind::DayOfWeek, CronDialect::Quartz) => (1, 7),
+        (CronFieldKind::DayOfWeek, _) => (0, 7),
+        _ => kind.range(),
+    };
+    if !(min..=max).contains(&value) {
+        return Err(format!("value {} is out of range {}-{}", value, min, max));
+    }
+
+    Ok(match (kind, dialect) {
+        (CronFieldKind::DayOfWeek, CronDialect::Quartz) => value - 1,
+        (CronFieldKind::DayOfWeek, _) => value % 7,
+        _ => value,
+    })
+}
+
+fn parse_number(text: &str) -> Result<u32, String> {
+    text.parse::<u32>()
+        .map_err(|_| format!("'{}' is not a number", text))
+}
+
+fn is_day_kind(kind: CronFieldKind) -> bool {
+    matches!(kind, CronFieldKind::DayOfMonth | CronFieldKind::DayOfWeek)
+}
+
+fn start_of(year: i32, month: u32, day: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(year, month, day)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .unwrap_or(NaiveDateTime::MAX)
+}
+
+fn last_day_of_month(date: NaiveDate) -> NaiveDate {
+    let (year, month) = match date.month() {
+        12 => (date.year() + 1, 1),
+        m => (date.year(), m + 1),
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|d| d.pred_opt())
+        .unwrap_or(date)
+}
+
+/// The Monday–Friday date closest to `day` in the month of `date`, without
+/// leaving the month.
+fn nearest_weekday(date: NaiveDate, day: u32) -> NaiveDate {
+    let target = date.with_day(day).unwrap_or(date);
+    let last = last_day_of_month(date).day();
+    match target.weekday().num_days_from_sunday() {
+        6 if day == 1 => target + Duration::days(2),
+        6 => target - Duration::days(1),
+        0 if day == last => target - Duration::days(2),
+        0 => target + Duration::days(1),
+        _ => target,
+    }
+}
+
+fn join_phrases(phrases: &[String]) -> String {
+    match phrases {
+        [] => String::new(),
+        [one] => one.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_dialects() {
+        assert_eq!(
+            CronExpression::parse("*/5 * * * *").unwrap().dialect,
+            CronDialect::Standard
+        );
+        assert_eq!(
+            CronExpression::parse("0 */5 * * * *").unwrap().dialect,
+            CronDialect::Seconds
+        );
+        assert_eq!(
+            CronExpression::parse("0 15 10 ? * MON-FRI")
+                .unwrap()
+                .dialect,
+            CronDialect::Quartz
+        );
+        assert_eq!(
+            CronExpression::parse("0 0 12 * * ? 2030").unwrap().dialect,
+            CronDialect::Quartz
+        );
+        assert!(CronExpression::parse("@daily").is_ok());
+
+        let weekend = CronExpression::parse("0 0 * * FRI-SUN").unwrap();
+        assert_eq!(weekend.day_of_week.expanded(), "0,5,6");
+    }
+
+    #[test]
+    fn test_validation() {
+        let err = CronExpression::parse("61 * * * *").unwrap_err();
+        assert!(err.contains("out of range 0-59"), "{}", err);
+        assert!(CronExpression::parse("* 24 * * *").is_err());
+        assert!(CronExpression::parse("* * * 13 *").is_err());
+        assert!(CronExpression::parse("*/0 * * * *").is_err());
+        assert!(CronExpression::parse("* * *").is_err());
+        assert!(CronExpression::parse("0 0 12 1 * MON").is_ok());
+        assert!(CronExpression::parse("0 0 12 1 * MON 2030").is_err());
+        assert!(CronExpression::parse("@reboot").is_err());
+    }
+
+    #[test]
+    fn test_explain() {
+        let explain = |s: &str| CronExpression::parse(s).unwrap().explain();
+        assert_eq!(
+            explain("30 9 * * 1-5"),
+            "At 09:30, on Monday through Friday"
+        );
+        assert_eq!(explain("*/15 * * * *"), "Every 15 minutes");
+        assert_eq!(
+            explain("0 0 L * ?"),
+            "At 00:00, on the last day of the month"
+        );
+        assert_eq!(
+            explain("0 0 12 ? JAN,JUL 6#2"),
+            "At 12:00, on the second Friday of the month, in January and in July"
+        );
+        assert_eq!(explain("5 * * * *"), "At minute 5");
+        assert_eq!(
+            explain("0 9-17 * * *"),
+            "At minute 0, every hour from 9 through 17"
+        );
+    }
+
+    #[test]
+    fn test_upcoming() {
+        let expression = CronExpression::parse("30 9 * * MON-FRI").unwrap();
+        let runs = expression.upcoming(utc("2024-03-08T10:00:00Z"), Zone::UTC, 3);
+        let runs: Vec<String> = runs.iter().map(|d| d.to_rfc3339()).collect();
+        assert_eq!(
+            runs,
+            [
+                "2024-03-11T09:30:00+00:00",
+                "2024-03-12T09:30:00+00:00",
+                "2024-03-13T09:30:00+00:00"
+            ]
+        );
+
+        let expression = CronExpression::parse("0 0 12 LW * ?").unwrap();
+        let runs = expression.upcoming(utc("2024-03-01T00:00:00Z"), Zone::UTC, 2);
+        assert_eq!(runs[0].to_rfc3339(), "2024-03-29T12:00:00+00:00");
+        assert_eq!(runs[1].to_rfc3339(), "2024-04-30T12:00:00+00:00");
+
+        let expression = CronExpression::parse("0 0 29 2 *").unwrap();
+        let runs = expression.upcoming(utc("2024-03-01T00:00:00Z"), Zone::UTC, 1);
+        assert_eq!(runs[0].to_rfc3339(), "2028-02-29T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_upcoming_dst() {
+        let zone = Zone::parse("Europe/Berlin").unwrap();
+        // 02:30 does not exist on 2024-03-31 in Berlin
+        let expression = CronExpression::parse("30 2 * * *").unwrap();
+        let runs = expression.upcoming(utc("2024-03-29T12:00:00Z"), zone, 3);
+        let days: Vec<u32> = runs.iter().map(|d| d.day()).collect();
+        assert_eq!(days, [30, 1, 2]);
+    }
+}
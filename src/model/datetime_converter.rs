@@ -1,27 +1,71 @@
-use std::slice::Iter;
+use std::{fmt::Write, slice::Iter};
 
-use chrono::{DateTime, Local, Utc};
+use chrono::{
+    format::StrftimeItems, DateTime, Datelike, Duration, FixedOffset, LocalResult, Months,
+    NaiveDate, NaiveDateTime, NaiveTime, Offset, SecondsFormat, TimeZone, Utc, Weekday,
+};
+use chrono_tz::{OffsetName, Tz, TZ_VARIANTS};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Seconds between the Unix epoch and the GPS epoch (1980-01-06T00:00:00Z).
+const GPS_EPOCH: i64 = 315_964_800;
+/// Seconds between the Unix epoch and the Cocoa reference date (2001-01-01T00:00:00Z).
+const MAC_EPOCH: i64 = 978_307_200;
+/// 100ns intervals between 1601-01-01 and the Unix epoch.
+const FILETIME_EPOCH: i128 = 116_444_736_000_000_000;
+/// 100ns intervals between 0001-01-01 and the Unix epoch.
+const DOTNET_EPOCH: i128 = 621_355_968_000_000_000;
+/// Julian day number of the Unix epoch.
+const JULIAN_UNIX_EPOCH: f64 = 2_440_587.5;
+/// Leap seconds inserted since the GPS epoch, as the Unix time they took effect.
+const LEAP_SECONDS: [i64; 18] = [
+    362793600, 394329600, 425865600, 489024000, 567993600, 631152000, 662688000, 709948800,
+    741484800, 773020800, 820454400, 867715200, 915148800, 1136073600, 1230768000, 1341100800,
+    1435708800, 1483228800,
+];
 
 pub enum DateTimeConverter {
     ISO8601,
+    ISOWeekDate,
+    ISOOrdinalDate,
     ISO9075,
     RFC3339,
     RFC7231,
-    Unix,
-    Timestamp,
     UTC,
+    Unix,
+    UnixMillis,
+    UnixMicros,
+    UnixNanos,
+    Excel,
+    FileTime,
+    DotNetTicks,
+    MacAbsolute,
+    GPS,
+    JulianDay,
+    Relative,
 }
 
 impl DateTimeConverter {
     pub fn iter() -> Iter<'static, DateTimeConverter> {
-        static CONVERTER: [DateTimeConverter; 7] = [
+        static CONVERTER: [DateTimeConverter; 18] = [
             DateTimeConverter::ISO8601,
+            DateTimeConverter::ISOWeekDate,
+            DateTimeConverter::ISOOrdinalDate,
             DateTimeConverter::ISO9075,
             DateTimeConverter::RFC3339,
             DateTimeConverter::RFC7231,
-            DateTimeConverter::Unix,
-            DateTimeConverter::Timestamp,
             DateTimeConverter::UTC,
+            DateTimeConverter::Unix,
+            DateTimeConverter::UnixMillis,
+            DateTimeConverter::UnixMicros,
+            DateTimeConverter::UnixNanos,
+            DateTimeConverter::Excel,
+            DateTimeConverter::FileTime,
+            DateTimeConverter::DotNetTicks,
+            DateTimeConverter::MacAbsolute,
+            DateTimeConverter::GPS,
+            DateTimeConverter::JulianDay,
+            DateTimeConverter::Relative,
         ];
         CONVERTER.iter()
     }
@@ -29,34 +73,1063 @@ impl DateTimeConverter {
     pub fn name(&self) -> String {
         match self {
             DateTimeConverter::ISO8601 => String::from("ISO 8601"),
+            DateTimeConverter::ISOWeekDate => String::from("ISO 8601 week date"),
+            DateTimeConverter::ISOOrdinalDate => String::from("ISO 8601 ordinal date"),
             DateTimeConverter::ISO9075 => String::from("ISO 9075"),
             DateTimeConverter::RFC3339 => String::from("RFC 3339"),
             DateTimeConverter::RFC7231 => String::from("RFC 7231"),
-            DateTimeConverter::Unix => String::from("Unix timestamp"),
-            DateTimeConverter::Timestamp => String::from("Timestamp"),
             DateTimeConverter::UTC => String::from("UTC format"),
+            DateTimeConverter::Unix => String::from("Unix timestamp"),
+            DateTimeConverter::UnixMillis => String::from("Unix milliseconds"),
+            DateTimeConverter::UnixMicros => String::from("Unix microseconds"),
+            DateTimeConverter::UnixNanos => String::from("Unix nanoseconds"),
+            DateTimeConverter::Excel => String::from("Excel / OLE date"),
+            DateTimeConverter::FileTime => String::from("Windows FILETIME"),
+            DateTimeConverter::DotNetTicks => String::from(".NET ticks"),
+            DateTimeConverter::MacAbsolute => String::from("Mac absolute time"),
+            DateTimeConverter::GPS => String::from("GPS time"),
+            DateTimeConverter::JulianDay => String::from("Julian day"),
+            DateTimeConverter::Relative => String::from("Relative"),
         }
     }
 
-    pub fn formatter(&self, datetime: DateTime<Local>) -> String {
+    pub fn formatter(&self, datetime: DateTime<FixedOffset>) -> String {
+        let nanos = unix_nanos(&datetime);
         match self {
-            DateTimeConverter::ISO8601 => datetime.format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+            DateTimeConverter::ISO8601 => datetime.format("%Y-%m-%dT%H:%M:%S%.3f%:z").to_string(),
+            DateTimeConverter::ISOWeekDate => datetime.format("%G-W%V-%u").to_string(),
+            DateTimeConverter::ISOOrdinalDate => datetime.format("%Y-%j").to_string(),
             DateTimeConverter::ISO9075 => datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
-            DateTimeConverter::RFC3339 => datetime.format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+            DateTimeConverter::RFC3339 => datetime.to_rfc3339_opts(SecondsFormat::Secs, false),
             DateTimeConverter::RFC7231 => datetime
                 .with_timezone(&Utc)
                 .format("%a, %d %b %Y %H:%M:%S GMT")
                 .to_string(),
-            DateTimeConverter::Unix => datetime.timestamp().to_string(),
-            DateTimeConverter::Timestamp => format!(
-                "{}{:03}",
-                datetime.timestamp(),
-                datetime.timestamp_subsec_millis()
-            ),
             DateTimeConverter::UTC => datetime
                 .with_timezone(&Utc)
-                .format("%a, %d %b %Y %H:%M:%S GMT")
-                .to_string(),
+                .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            DateTimeConverter::Unix => datetime.timestamp().to_string(),
+            DateTimeConverter::UnixMillis => nanos.div_euclid(1_000_000).to_string(),
+            DateTimeConverter::UnixMicros => nanos.div_euclid(1_000).to_string(),
+            DateTimeConverter::UnixNanos => nanos.to_string(),
+            DateTimeConverter::Excel => {
+                // serial days since 1899-12-30 in wall-clock time, as Excel has no zones
+                let local = datetime.naive_local().and_utc();
+                let days =
+                    (unix_nanos(&local.fixed_offset()) as f64 / 1e9 + 2_209_161_600.0) / 86_400.0;
+                format!("{:.6}", days)
+            }
+            DateTimeConverter::FileTime => (nanos.div_euclid(100) + FILETIME_EPOCH).to_string(),
+            DateTimeConverter::DotNetTicks => (nanos.div_euclid(100) + DOTNET_EPOCH).to_string(),
+            DateTimeConverter::MacAbsolute => ((nanos as f64 / 1e9) - MAC_EPOCH as f64).to_string(),
+            DateTimeConverter::GPS => {
+                let timestamp = datetime.timestamp();
+                let leaps = LEAP_SECONDS.iter().filter(|&&t| t <= timestamp).count() as i64;
+                let gps = timestamp - GPS_EPOCH + leaps;
+                format!(
+                    "{} (week {}, {}s)",
+                    gps,
+                    gps.div_euclid(604_800),
+                    gps.rem_euclid(604_800)
+                )
+            }
+            DateTimeConverter::JulianDay => {
+                format!("{:.6}", nanos as f64 / 1e9 / 86_400.0 + JULIAN_UNIX_EPOCH)
+            }
+            DateTimeConverter::Relative => {
+                DateTimeConverter::relative(datetime, Utc::now().fixed_offset())
+            }
+        }
+    }
+
+    /// Formats with a user supplied strftime pattern, reporting invalid
+    /// specifiers instead of panicking.
+    pub fn custom(datetime: DateTime<FixedOffset>, pattern: &str) -> Result<String, String> {
+        let items = StrftimeItems::new(pattern)
+            .parse()
+            .map_err(|_| format!("Invalid format pattern: {}", pattern))?;
+        let mut out = String::new();
+        write!(out, "{}", datetime.format_with_items(items.iter()))
+            .map_err(|_| format!("Pattern cannot be applied to this date: {}", pattern))?;
+        Ok(out)
+    }
+
+    /// Human readable distance between `datetime` and `now`, e.g. `3 days ago`.
+    pub fn relative(datetime: DateTime<FixedOffset>, now: DateTime<FixedOffset>) -> String {
+        let delta = datetime.signed_duration_since(now);
+        let secs = delta.num_seconds().abs();
+        if secs < 5 {
+            return String::from("just now");
+        }
+
+        let (value, unit) = [
+            (365 * 86_400, "year"),
+            (30 * 86_400, "month"),
+            (7 * 86_400, "week"),
+            (86_400, "day"),
+            (3_600, "hour"),
+            (60, "minute"),
+            (1, "second"),
+        ]
+        .iter()
+        .find(|(size, _)| secs >= *size)
+        .map(|(size, unit)| (secs / size, *unit))
+        .unwrap_or((secs, "second"));
+
+        let plural = if value == 1 { "" } else { "s" };
+        if delta.num_seconds() < 0 {
+            format!("{} {}{} ago", value, unit, plural)
+        } else {
+            format!("in {} {}{}", value, unit, plural)
+        }
+    }
+}
+
+fn unix_nanos(datetime: &DateTime<FixedOffset>) -> i128 {
+    datetime.timestamp() as i128 * 1_000_000_000 + datetime.timestamp_subsec_nanos() as i128
+}
+
+/// A time zone used to interpret input or render output: either the
+/// system's local zone or a zone from the IANA database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Zone {
+    #[default]
+    Local,
+    Iana(Tz),
+}
+
+/// Zones are stored by name, e.g. `"Local"` or `"Europe/Berlin"`.
+impl Serialize for Zone {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Zone {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Zone::parse(&name).ok_or_else(|| de::Error::custom(format!("unknown time zone {}", name)))
+    }
+}
+
+/// Result of placing a wall-clock time into a zone. `warning` is set when the
+/// local time was ambiguous (DST fall-back) or did not exist (DST spring-forward).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZonedDateTime {
+    pub datetime: DateTime<FixedOffset>,
+    pub warning: Option<String>,
+}
+
+impl Zone {
+    pub const UTC: Zone = Zone::Iana(Tz::UTC);
+
+    pub fn all() -> impl Iterator<Item = Zone> {
+        std::iter::once(Zone::Local).chain(TZ_VARIANTS.iter().map(|tz| Zone::Iana(*tz)))
+    }
+
+    pub fn parse(name: &str) -> Option<Zone> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("local") {
+            return Some(Zone::Local);
+        }
+        name.parse::<Tz>().ok().map(Zone::Iana)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Zone::Local => "Local",
+            Zone::Iana(tz) => tz.name(),
+        }
+    }
+
+    /// Short name of the offset in effect at `utc`, e.g. `CEST` or `+0530`.
+    pub fn abbreviation(&self, utc: DateTime<Utc>) -> String {
+        match self {
+            Zone::Local => utc.with_timezone(&chrono::Local).format("%Z").to_string(),
+            Zone::Iana(tz) => {
+                let offset = tz.offset_from_utc_datetime(&utc.naive_utc());
+                match offset.abbreviation() {
+                    Some(abbr) => abbr.to_string(),
+                    None => offset.fix().to_string(),
+                }
+            }
+        }
+    }
+
+    pub fn from_utc(&self, utc: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self {
+            Zone::Local => utc.with_timezone(&chrono::Local).fixed_offset(),
+            Zone::Iana(tz) => utc.with_timezone(tz).fixed_offset(),
+        }
+    }
+
+    pub fn from_timestamp(&self, secs: i64, nsecs: u32) -> Option<DateTime<FixedOffset>> {
+        DateTime::from_timestamp(secs, nsecs).map(|utc| self.from_utc(utc))
+    }
+
+    /// Interprets a wall-clock time as local to this zone.
+    pub fn from_local(&self, naive: NaiveDateTime) -> Result<ZonedDateTime, String> {
+        match self {
+            Zone::Local => resolve_local(&chrono::Local, naive),
+            Zone::Iana(tz) => resolve_local(tz, naive),
+        }
+    }
+}
+
+fn resolve_local<T: TimeZone>(tz: &T, naive: NaiveDateTime) -> Result<ZonedDateTime, String> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(datetime) => Ok(ZonedDateTime {
+            datetime: datetime.fixed_offset(),
+            warning: None,
+        }),
+        LocalResult::Ambiguous(earliest, latest) => {
+            let earliest = earliest.fixed_offset();
+            let latest = latest.fixed_offset();
+            Ok(ZonedDateTime {
+                warning: Some(format!(
+                    "{} is ambiguous (DST fall-back): using offset {} instead of {}",
+                    naive,
+                    earliest.offset(),
+                    latest.offset()
+                )),
+                datetime: earliest,
+            })
+        }
+        LocalResult::None => {
+            // Skipped by a DST gap: keep the offset in effect before the transition,
+            // which moves the wall clock forward by the size of the gap.
+            let before = tz
+                .from_local_datetime(&(naive - Duration::hours(24)))
+                .earliest()
+                .ok_or_else(|| format!("{} does not exist in this time zone", naive))?;
+            let utc = naive - Duration::seconds(before.offset().fix().local_minus_utc() as i64);
+            let datetime = tz.from_utc_datetime(&utc).fixed_offset();
+            Ok(ZonedDateTime {
+                warning: Some(format!(
+                    "{} does not exist (DST gap): shifted to {}",
+                    naive,
+                    datetime.naive_local()
+                )),
+                datetime,
+            })
+        }
+    }
+}
+
+/// Input formats understood by the date-time converter, in the order
+/// [`DateFormat::detect`] tries them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DateFormat {
+    UnixSeconds,
+    UnixMillis,
+    UnixMicros,
+    UnixNanos,
+    RFC3339,
+    HttpDate,
+    RFC2822,
+    CommonLog,
+    ISOLocal,
+    SlashDateTime,
+    Date,
+    Syslog,
+}
+
+impl DateFormat {
+    pub fn iter() -> Iter<'static, DateFormat> {
+        static FORMATS: [DateFormat; 12] = [
+            DateFormat::UnixSeconds,
+            DateFormat::UnixMillis,
+            DateFormat::UnixMicros,
+            DateFormat::UnixNanos,
+            DateFormat::RFC3339,
+            DateFormat::HttpDate,
+            DateFormat::RFC2822,
+            DateFormat::CommonLog,
+            DateFormat::ISOLocal,
+            DateFormat::SlashDateTime,
+            DateFormat::Date,
+            DateFormat::Syslog,
+        ];
+        FORMATS.iter()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DateFormat::UnixSeconds => "Unix seconds",
+            DateFormat::UnixMillis => "Unix milliseconds",
+            DateFormat::UnixMicros => "Unix microseconds",
+            DateFormat::UnixNanos => "Unix nanoseconds",
+            DateFormat::RFC3339 => "RFC 3339",
+            DateFormat::HttpDate => "HTTP date",
+            DateFormat::RFC2822 => "RFC 2822",
+            DateFormat::CommonLog => "Common log format",
+            DateFormat::ISOLocal => "ISO 8601 local",
+            DateFormat::SlashDateTime => "YYYY/MM/DD time",
+            DateFormat::Date => "Date only",
+            DateFormat::Syslog => "Syslog",
+        }
+    }
+
+    pub fn example(&self) -> &'static str {
+        match self {
+            DateFormat::UnixSeconds => "1709632800",
+            DateFormat::UnixMillis => "1709632800000",
+            DateFormat::UnixMicros => "1709632800000000",
+            DateFormat::UnixNanos => "1709632800000000000",
+            DateFormat::RFC3339 => "2024-03-05T10:00:00+08:00",
+            DateFormat::HttpDate => "Tue, 05 Mar 2024 02:00:00 GMT",
+            DateFormat::RFC2822 => "Tue, 5 Mar 2024 10:00:00 +0800",
+            DateFormat::CommonLog => "05/Mar/2024:10:00:00 +0800",
+            DateFormat::ISOLocal => "2024-03-05 10:00:00",
+            DateFormat::SlashDateTime => "2024/03/05 10:00",
+            DateFormat::Date => "2024-03-05",
+            DateFormat::Syslog => "Mar  5 10:00:00",
+        }
+    }
+
+    /// Tries every format in rank order and returns the first that parses.
+    pub fn detect(input: &str, zone: Zone) -> Option<(DateFormat, ZonedDateTime)> {
+        DateFormat::iter()
+            .filter(|format| format.plausible(input))
+            .find_map(|format| format.parse(input, zone).ok().map(|dt| (*format, dt)))
+    }
+
+    /// Cheap pre-check used by detection so that e.g. a millisecond epoch is
+    /// not also accepted as a (far future) seconds epoch, and a compact
+    /// `20240305` date is not taken for seconds.
+    fn plausible(&self, input: &str) -> bool {
+        let input = input.trim();
+        let digits = input
+            .trim_start_matches('-')
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .len();
+        match self {
+            DateFormat::UnixSeconds => {
+                (1..=11).contains(&digits)
+                    && !(input.len() == 8 && NaiveDate::parse_from_str(input, "%Y%m%d").is_ok())
+            }
+            DateFormat::UnixMillis => (12..=14).contains(&digits),
+            DateFormat::UnixMicros => (15..=17).contains(&digits),
+            DateFormat::UnixNanos => (18..=19).contains(&digits),
+            _ => true,
+        }
+    }
+
+    /// Parses `input` in this format. Formats without an explicit offset are
+    /// interpreted as wall-clock time in `zone`.
+    pub fn parse(&self, input: &str, zone: Zone) -> Result<ZonedDateTime, String> {
+        let input = input.trim();
+        let fixed = |datetime: DateTime<FixedOffset>| {
+            Ok(ZonedDateTime {
+                datetime,
+                warning: None,
+            })
+        };
+
+        let parsed = match self {
+            DateFormat::UnixSeconds => parse_epoch(input, 1_000_000_000).map(fixed),
+            DateFormat::UnixMillis => parse_epoch(input, 1_000_000).map(fixed),
+            DateFormat::UnixMicros => parse_epoch(input, 1_000).map(fixed),
+            DateFormat::UnixNanos => parse_epoch(input, 1).map(fixed),
+            DateFormat::RFC3339 => DateTime::parse_from_rfc3339(input).ok().map(fixed),
+            DateFormat::RFC2822 => DateTime::parse_from_rfc2822(input).ok().map(fixed),
+            DateFormat::HttpDate => {
+                // IMF-fixdate, obsolete RFC 850 and asctime() forms, always in GMT
+                let naive = match input.strip_suffix(" GMT") {
+                    Some(rest) => ["%a, %d %b %Y %H:%M:%S", "%A, %d-%b-%y %H:%M:%S"]
+                        .iter()
+                        .find_map(|f| NaiveDateTime::parse_from_str(rest, f).ok()),
+                    None => NaiveDateTime::parse_from_str(input, "%a %b %e %H:%M:%S %Y").ok(),
+                };
+                naive.map(|naive| fixed(naive.and_utc().fixed_offset()))
+            }
+            DateFormat::CommonLog => {
+                DateTime::parse_from_str(input.trim_matches(['[', ']']), "%d/%b/%Y:%H:%M:%S %z")
+                    .ok()
+                    .map(fixed)
+            }
+            DateFormat::ISOLocal => parse_naive(
+                input,
+                zone,
+                &[
+                    "%Y-%m-%dT%H:%M:%S%.f",
+                    "%Y-%m-%d %H:%M:%S%.f",
+                    "%Y-%m-%dT%H:%M",
+                    "%Y-%m-%d %H:%M",
+                ],
+            ),
+            DateFormat::SlashDateTime => {
+                parse_naive(input, zone, &["%Y/%m/%d %H:%M:%S%.f", "%Y/%m/%d %H:%M"])
+            }
+            DateFormat::Date => ["%Y-%m-%d", "%Y/%m/%d", "%Y%m%d"]
+                .iter()
+                .find_map(|f| NaiveDate::parse_from_str(input, f).ok())
+                .map(|date| zone.from_local(date.and_time(NaiveTime::MIN))),
+            DateFormat::Syslog => {
+                // syslog omits the year, assume the current one
+                let year = zone.from_utc(Utc::now()).year();
+                parse_naive(&format!("{} {}", year, input), zone, &["%Y %b %e %H:%M:%S"])
+            }
+        };
+
+        parsed.unwrap_or_else(|| Err(format!("'{}' is not a valid {} value", input, self.name())))
+    }
+}
+
+fn parse_epoch(input: &str, unit_nanos: i128) -> Option<DateTime<FixedOffset>> {
+    let (int, frac) = input.split_once('.').unwrap_or((input, ""));
+    if frac.len() > 9 || !frac.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let negative = int.starts_with('-');
+    let whole = int.parse::<i128>().ok()?;
+    let frac_nanos = if frac.is_empty() {
+        0
+    } else {
+        format!("{:0<9}", frac).parse::<i128>().ok()? * unit_nanos / 1_000_000_000
+    };
+    let total = whole * unit_nanos + if negative { -frac_nanos } else { frac_nanos };
+    let secs = i64::try_from(total.div_euclid(1_000_000_000)).ok()?;
+    let nanos = total.rem_euclid(1_000_000_000) as u32;
+    DateTime::from_timestamp(secs, nanos).map(|utc| utc.fixed_offset())
+}
+
+fn parse_naive(input: &str, zone: Zone, formats: &[&str]) -> Option<Result<ZonedDateTime, String>> {
+    formats
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(input, f).ok())
+        .map(|naive| zone.from_local(naive))
+}
+
+/// A signed, calendar-aware duration. Months and days follow the wall clock of
+/// the zone they are applied in, `millis` is an exact amount of elapsed time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DateDuration {
+    pub months: i64,
+    pub days: i64,
+    pub millis: i64,
+}
+
+const OUT_OF_RANGE: &str = "duration out of range";
+
+impl DateDuration {
+    /// Parses shorthand such as `+3d 4h`, `-1w2h30m` or `1mo 500ms`, or an
+    /// ISO 8601 duration such as `P1M2DT3H` / `-PT1.5S`.
+    pub fn parse(input: &str) -> Result<DateDuration, String> {
+        let input = input.trim();
+        let (negative, rest) = match input.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, input.strip_prefix('+').unwrap_or(input)),
+        };
+
+        let duration = if rest.starts_with(['P', 'p']) {
+            Self::parse_iso(&rest[1..])
+        } else {
+            Self::parse_shorthand(rest)
+        }
+        .map_err(|e| format!("Invalid duration '{}': {}", input, e))?;
+
+        Ok(if negative {
+            duration.negate()
+        } else {
+            duration
+        })
+    }
+
+    fn parse_iso(input: &str) -> Result<DateDuration, String> {
+        let mut duration = DateDuration::default();
+        let mut in_time = false;
+        let mut number = String::new();
+        let mut seen_any = false;
+
+        for c in input.chars() {
+            match c {
+                '0'..='9' | '.' | ',' => number.push(if c == ',' { '.' } else { c }),
+                'T' | 't' if number.is_empty() => in_time = true,
+                _ => {
+                    let value = number
+                        .parse::<f64>()
+                        .map_err(|_| format!("missing number before '{}'", c))?;
+                    number.clear();
+                    seen_any = true;
+                    let (total, amount, factor) = match (in_time, c.to_ascii_uppercase()) {
+                        (false, 'Y') => (&mut duration.months, whole(value)?, 12),
+                        (false, 'M') => (&mut duration.months, whole(value)?, 1),
+                        (false, 'W') => (&mut duration.days, whole(value)?, 7),
+                        (false, 'D') => (&mut duration.days, whole(value)?, 1),
+                        (true, 'H') => (&mut duration.millis, rounded(value * 3_600_000.0)?, 1),
+                        (true, 'M') => (&mut duration.millis, rounded(value * 60_000.0)?, 1),
+                        (true, 'S') => (&mut duration.millis, rounded(value * 1_000.0)?, 1),
+                        _ => return Err(format!("unexpected designator '{}'", c)),
+                    };
+                    accumulate(total, amount, factor)?;
+                }
+            }
         }
+
+        if !number.is_empty() || !seen_any {
+            return Err(String::from("expected a designator such as D or H"));
+        }
+        Ok(duration)
+    }
+
+    fn parse_shorthand(input: &str) -> Result<DateDuration, String> {
+        let mut duration = DateDuration::default();
+        let mut chars = input.chars().peekable();
+        let mut seen_any = false;
+
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+                continue;
+            }
+
+            let sign = match c {
+                '-' => -1,
+                '+' => 1,
+                _ => 0,
+            };
+            if sign != 0 {
+                chars.next();
+            }
+
+            let mut digits = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                digits.push(d);
+                chars.next();
+            }
+            let mut unit = String::new();
+            while let Some(&u) = chars.peek().filter(|u| u.is_alphabetic()) {
+                unit.push(u);
+                chars.next();
+            }
+
+            let value = digits
+                .parse::<i64>()
+                .map_err(|_| format!("expected a number before '{}'", unit))?
+                * if sign < 0 { -1 } else { 1 };
+            let (total, factor) = match unit.to_lowercase().as_str() {
+                "y" | "yr" | "year" | "years" => (&mut duration.months, 12),
+                "mo" | "month" | "months" => (&mut duration.months, 1),
+                "w" | "week" | "weeks" => (&mut duration.days, 7),
+                "d" | "day" | "days" => (&mut duration.days, 1),
+                "h" | "hour" | "hours" => (&mut duration.millis, 3_600_000),
+                "m" | "min" | "mins" | "minute" | "minutes" => (&mut duration.millis, 60_000),
+                "s" | "sec" | "secs" | "second" | "seconds" => (&mut duration.millis, 1_000),
+                "ms" => (&mut duration.millis, 1),
+                "" => return Err(format!("missing unit after {}", digits)),
+                other => return Err(format!("unknown unit '{}'", other)),
+            };
+            accumulate(total, value, factor)?;
+            seen_any = true;
+        }
+
+        if !seen_any {
+            return Err(String::from("empty duration"));
+        }
+        Ok(duration)
+    }
+
+    pub fn negate(self) -> DateDuration {
+        DateDuration {
+            months: -self.months,
+            days: -self.days,
+            millis: -self.millis,
+        }
+    }
+
+    /// Adds the duration to `datetime`. Calendar parts are applied to the wall
+    /// clock in `zone`, so `+1d` across a DST change keeps the same local time.
+    pub fn apply(
+        &self,
+        datetime: DateTime<FixedOffset>,
+        zone: Zone,
+    ) -> Result<ZonedDateTime, String> {
+        let mut zoned = ZonedDateTime {
+            datetime,
+            warning: None,
+        };
+
+        if self.months != 0 || self.days != 0 {
+            let local = zone.from_utc(datetime.with_timezone(&Utc)).naive_local();
+            let months = u32::try_from(self.months.unsigned_abs())
+                .map(Months::new)
+                .map_err(|_| OUT_OF_RANGE)?;
+            let days = Duration::try_days(self.days).ok_or(OUT_OF_RANGE)?;
+            let shifted = if self.months >= 0 {
+                local.checked_add_months(months)
+            } else {
+                local.checked_sub_months(months)
+            }
+            .and_then(|d| d.checked_add_signed(days))
+            .ok_or(OUT_OF_RANGE)?;
+            zoned = zone.from_local(shifted)?;
+        }
+
+        let millis = Duration::try_milliseconds(self.millis).ok_or(OUT_OF_RANGE)?;
+        zoned.datetime = zoned
+            .datetime
+            .checked_add_signed(millis)
+            .ok_or(OUT_OF_RANGE)?;
+        Ok(zoned)
+    }
+
+    /// Normalized ISO 8601 representation, e.g. `P1Y2M3DT4H`.
+    pub fn to_iso(&self) -> String {
+        if *self == DateDuration::default() {
+            return String::from("PT0S");
+        }
+
+        // a leading sign only when every component points the same way,
+        // mixed durations keep a sign per component
+        let parts = [self.months, self.days, self.millis];
+        let negative = parts.iter().all(|v| *v <= 0);
+        let abs = if negative { self.negate() } else { *self };
+        let mut out = String::from(if negative { "-P" } else { "P" });
+        for (value, unit) in [
+            (abs.months / 12, 'Y'),
+            (abs.months % 12, 'M'),
+            (abs.days, 'D'),
+        ] {
+            if value != 0 {
+                let _ = write!(out, "{}{}", value, unit);
+            }
+        }
+        if abs.millis != 0 {
+            out.push('T');
+            let secs = abs.millis / 1_000;
+            for (value, unit) in [(secs / 3_600, 'H'), (secs % 3_600 / 60, 'M')] {
+                if value != 0 {
+                    let _ = write!(out, "{}{}", value, unit);
+                }
+            }
+            match (secs % 60, abs.millis % 1_000) {
+                (0, 0) => (),
+                (s, 0) => {
+                    let _ = write!(out, "{}S", s);
+                }
+                (s, ms) => {
+                    let _ = write!(out, "{}.{:03}S", s, ms);
+                }
+            }
+        }
+        out
+    }
+}
+
+fn whole(value: f64) -> Result<i64, String> {
+    if value.fract() != 0.0 {
+        Err(String::from("only time components may be fractional"))
+    } else {
+        rounded(value)
+    }
+}
+
+/// Rounds to an integer, `as` would saturate huge values silently.
+fn rounded(value: f64) -> Result<i64, String> {
+    let value = value.round();
+    if value.abs() < i64::MAX as f64 {
+        Ok(value as i64)
+    } else {
+        Err(String::from(OUT_OF_RANGE))
+    }
+}
+
+/// Adds `value * factor` to a duration component. `i64::MIN` is refused too,
+/// so every duration can be negated.
+fn accumulate(total: &mut i64, value: i64, factor: i64) -> Result<(), String> {
+    *total = value
+        .checked_mul(factor)
+        .and_then(|value| total.checked_add(value))
+        .filter(|total| *total != i64::MIN)
+        .ok_or(OUT_OF_RANGE)?;
+    Ok(())
+}
+
+/// Difference between two instants, both as totals and as a calendar breakdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateDifference {
+    pub negative: bool,
+    pub total: Duration,
+    pub years: i64,
+    pub months: i64,
+    pub days: i64,
+    pub hours: i64,
+    pub minutes: i64,
+    pub seconds: i64,
+}
+
+impl DateDifference {
+    pub fn between(start: DateTime<FixedOffset>, end: DateTime<FixedOffset>) -> DateDifference {
+        let negative = end < start;
+        let (from, to) = if negative {
+            (end.naive_utc(), start.naive_utc())
+        } else {
+            (start.naive_utc(), end.naive_utc())
+        };
+
+        let mut months =
+            (to.year() as i64 - from.year() as i64) * 12 + to.month() as i64 - from.month() as i64;
+        let add = |months: i64| from.checked_add_months(Months::new(months.max(0) as u32));
+        while months > 0 && add(months).is_none_or(|d| d > to) {
+            months -= 1;
+        }
+        let rest = to - add(months).unwrap_or(from);
+
+        DateDifference {
+            negative,
+            total: end.signed_duration_since(start),
+            years: months / 12,
+            months: months % 12,
+            days: rest.num_days(),
+            hours: rest.num_hours() % 24,
+            minutes: rest.num_minutes() % 60,
+            seconds: rest.num_seconds() % 60,
+        }
+    }
+
+    /// The signed total expressed in several units.
+    pub fn units(&self) -> Vec<(&'static str, f64)> {
+        let secs = self.total.num_milliseconds() as f64 / 1_000.0;
+        vec![
+            ("Seconds", secs),
+            ("Minutes", secs / 60.0),
+            ("Hours", secs / 3_600.0),
+            ("Days", secs / 86_400.0),
+            ("Weeks", secs / 604_800.0),
+        ]
+    }
+
+    pub fn human(&self) -> String {
+        let mut parts: Vec<String> = [
+            (self.years, "year"),
+            (self.months, "month"),
+            (self.days, "day"),
+        ]
+        .iter()
+        .filter(|(value, _)| *value != 0)
+        .map(|(value, unit)| format!("{} {}{}", value, unit, if *value == 1 { "" } else { "s" }))
+        .collect();
+        parts.push(format!(
+            "{:02}:{:02}:{:02}",
+            self.hours, self.minutes, self.seconds
+        ));
+        let text = parts.join(" ");
+        if self.negative {
+            format!("-{}", text)
+        } else {
+            text
+        }
+    }
+}
+
+/// Number of Monday–Friday days in `[start, end)`, negative when `end` is
+/// before `start`.
+pub fn business_days(start: NaiveDate, end: NaiveDate) -> i64 {
+    if end < start {
+        return -business_days(end, start);
+    }
+
+    let days = (end - start).num_days();
+    let mut count = days / 7 * 5;
+    let mut day = start + Duration::days(days / 7 * 7);
+    while day < end {
+        if day.weekday().number_from_monday() <= 5 {
+            count += 1;
+        }
+        day = day.succ_opt().unwrap_or(end);
+    }
+    count
+}
+
+/// Calendar facts about a single date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarInfo {
+    pub weekday: Weekday,
+    pub iso_year: i32,
+    pub iso_week: u32,
+    pub day_of_year: u32,
+    pub quarter: u32,
+    pub leap_year: bool,
+    pub days_in_month: u32,
+}
+
+impl CalendarInfo {
+    pub fn of(date: NaiveDate) -> CalendarInfo {
+        let week = date.iso_week();
+        let first_of_month = date.with_day(1).unwrap_or(date);
+        let days_in_month = (first_of_month + Months::new(1))
+            .signed_duration_since(first_of_month)
+            .num_days() as u32;
+
+        CalendarInfo {
+            weekday: date.weekday(),
+            iso_year: week.year(),
+            iso_week: week.week(),
+            day_of_year: date.ordinal(),
+            quarter: (date.month() - 1) / 3 + 1,
+            leap_year: date.leap_year(),
+            days_in_month,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn naive(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_from_local_uses_zone_offset() {
+        let zone = Zone::parse("Asia/Shanghai").unwrap();
+        let zoned = zone.from_local(naive("2024-03-05 10:00:00")).unwrap();
+        assert_eq!(zoned.datetime.timestamp(), 1709604000);
+        assert_eq!(zoned.warning, None);
+    }
+
+    #[test]
+    fn test_from_local_dst_ambiguous() {
+        let zone = Zone::parse("Europe/Berlin").unwrap();
+        let zoned = zone.from_local(naive("2024-10-27 02:30:00")).unwrap();
+        assert_eq!(zoned.datetime.offset().local_minus_utc(), 7200);
+        assert!(zoned.warning.unwrap().contains("ambiguous"));
+    }
+
+    #[test]
+    fn test_from_local_dst_gap() {
+        let zone = Zone::parse("Europe/Berlin").unwrap();
+        let zoned = zone.from_local(naive("2024-03-31 02:30:00")).unwrap();
+        assert_eq!(zoned.datetime.naive_local(), naive("2024-03-31 03:30:00"));
+        assert!(zoned.warning.unwrap().contains("does not exist"));
+    }
+
+    #[test]
+    fn test_zone_abbreviation() {
+        let zone = Zone::parse("Europe/Berlin").unwrap();
+        let utc = DateTime::from_timestamp(1719835200, 0).unwrap();
+        assert_eq!(zone.abbreviation(utc), "CEST");
+        assert_eq!(zone.from_utc(utc).format("%H:%M").to_string(), "14:00");
+    }
+
+    #[test]
+    fn test_detect_formats() {
+        let zone = Zone::parse("Asia/Shanghai").unwrap();
+        let cases = [
+            ("1709604000", DateFormat::UnixSeconds),
+            ("1709604000000", DateFormat::UnixMillis),
+            ("1709604000000000", DateFormat::UnixMicros),
+            ("1709604000000000000", DateFormat::UnixNanos),
+            ("2024-03-05T02:00:00Z", DateFormat::RFC3339),
+            ("Tue, 05 Mar 2024 02:00:00 GMT", DateFormat::HttpDate),
+            ("Tue, 5 Mar 2024 10:00:00 +0800", DateFormat::RFC2822),
+            ("[05/Mar/2024:10:00:00 +0800]", DateFormat::CommonLog),
+            ("2024-03-05 10:00:00", DateFormat::ISOLocal),
+            ("2024/03/05 10:00", DateFormat::SlashDateTime),
+        ];
+        for (input, expected) in cases {
+            let (format, zoned) = DateFormat::detect(input, zone).unwrap();
+            assert_eq!(format, expected, "{}", input);
+            assert_eq!(zoned.datetime.timestamp(), 1709604000, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_detect_fallbacks() {
+        let zone = Zone::UTC;
+        let (format, zoned) = DateFormat::detect("2024-03-05", zone).unwrap();
+        assert_eq!(format, DateFormat::Date);
+        assert_eq!(zoned.datetime.timestamp(), 1709596800);
+
+        // eight digits that make a calendar date are a date, not seconds
+        let (format, zoned) = DateFormat::detect("20240305", zone).unwrap();
+        assert_eq!(format, DateFormat::Date);
+        assert_eq!(zoned.datetime.timestamp(), 1709596800);
+        let (format, _) = DateFormat::detect("20241305", zone).unwrap();
+        assert_eq!(format, DateFormat::UnixSeconds);
+
+        let (format, zoned) = DateFormat::detect("1709604000.25", zone).unwrap();
+        assert_eq!(format, DateFormat::UnixSeconds);
+        assert_eq!(zoned.datetime.timestamp_subsec_millis(), 250);
+
+        assert_eq!(
+            DateFormat::detect("Sun Nov  6 08:49:37 1994", zone)
+                .unwrap()
+                .0,
+            DateFormat::HttpDate
+        );
+        assert_eq!(
+            DateFormat::detect("Mar  5 10:00:00", zone).unwrap().0,
+            DateFormat::Syslog
+        );
+        assert!(DateFormat::detect("not a date", zone).is_none());
+    }
+
+    #[test]
+    fn test_parse_override() {
+        // a seconds-looking number can still be forced to milliseconds
+        let zoned = DateFormat::UnixMillis.parse("1000", Zone::UTC).unwrap();
+        assert_eq!(zoned.datetime.timestamp(), 1);
+        assert!(DateFormat::RFC3339.parse("2024-03-05", Zone::UTC).is_err());
+    }
+
+    #[test]
+    fn test_output_formats() {
+        let datetime = DateTime::parse_from_rfc3339("2024-03-05T10:00:00.5+08:00").unwrap();
+        let format = |converter: DateTimeConverter| converter.formatter(datetime);
+
+        assert_eq!(
+            format(DateTimeConverter::ISO8601),
+            "2024-03-05T10:00:00.500+08:00"
+        );
+        assert_eq!(
+            format(DateTimeConverter::RFC3339),
+            "2024-03-05T10:00:00+08:00"
+        );
+        assert_eq!(format(DateTimeConverter::UTC), "2024-03-05T02:00:00.500Z");
+        assert_eq!(format(DateTimeConverter::ISOWeekDate), "2024-W10-2");
+        assert_eq!(format(DateTimeConverter::ISOOrdinalDate), "2024-065");
+        assert_eq!(format(DateTimeConverter::UnixMillis), "1709604000500");
+        assert_eq!(format(DateTimeConverter::UnixNanos), "1709604000500000000");
+        assert_eq!(format(DateTimeConverter::Excel), "45356.416672");
+        assert_eq!(format(DateTimeConverter::FileTime), "133540776005000000");
+        assert_eq!(format(DateTimeConverter::DotNetTicks), "638452008005000000");
+        assert_eq!(format(DateTimeConverter::MacAbsolute), "731296800.5");
+        assert_eq!(
+            format(DateTimeConverter::GPS),
+            "1393639218 (week 2304, 180018s)"
+        );
+        assert_eq!(format(DateTimeConverter::JulianDay), "2460374.583339");
+    }
+
+    #[test]
+    fn test_custom_and_relative() {
+        let datetime = DateTime::parse_from_rfc3339("2024-03-05T10:00:00+08:00").unwrap();
+        assert_eq!(
+            DateTimeConverter::custom(datetime, "%d.%m.%Y %Hh").unwrap(),
+            "05.03.2024 10h"
+        );
+        assert!(DateTimeConverter::custom(datetime, "%Q").is_err());
+
+        let now = datetime + Duration::days(3);
+        assert_eq!(DateTimeConverter::relative(datetime, now), "3 days ago");
+        assert_eq!(DateTimeConverter::relative(now, datetime), "in 3 days");
+        assert_eq!(DateTimeConverter::relative(datetime, datetime), "just now");
+    }
+
+    #[test]
+    fn test_duration_parse() {
+        let parsed = DateDuration::parse("+3d 4h").unwrap();
+        assert_eq!(
+            parsed,
+            DateDuration {
+                months: 0,
+                days: 3,
+                millis: 4 * 3_600_000
+            }
+        );
+        assert_eq!(parsed.to_iso(), "P3DT4H");
+
+        let parsed = DateDuration::parse("P1M2DT3H").unwrap();
+        assert_eq!(
+            parsed,
+            DateDuration {
+                months: 1,
+                days: 2,
+                millis: 3 * 3_600_000
+            }
+        );
+
+        let parsed = DateDuration::parse("-PT1.5S").unwrap();
+        assert_eq!(parsed.millis, -1_500);
+        assert_eq!(parsed.to_iso(), "-PT1.500S");
+
+        assert_eq!(
+            DateDuration::parse("1y 2mo -1w").unwrap().to_iso(),
+            "P1Y2M-7D"
+        );
+        assert!(DateDuration::parse("3 parsecs").is_err());
+        assert_eq!(
+            DateDuration::parse("9999999999999999h"),
+            Err(String::from(
+                "Invalid duration '9999999999999999h': duration out of range"
+            ))
+        );
+        assert!(DateDuration::parse("P99999999999999999999Y").is_err());
+        assert!(DateDuration::parse("P1.5D").is_err());
+        assert!(DateDuration::parse("").is_err());
+    }
+
+    #[test]
+    fn test_duration_apply() {
+        let zone = Zone::parse("Europe/Berlin").unwrap();
+        let start = zone
+            .from_local(naive("2024-03-30 12:00:00"))
+            .unwrap()
+            .datetime;
+
+        // one calendar day across spring-forward keeps the wall clock
+        let next = DateDuration::parse("1d")
+            .unwrap()
+            .apply(start, zone)
+            .unwrap();
+        assert_eq!(next.datetime.naive_local(), naive("2024-03-31 12:00:00"));
+        assert_eq!((next.datetime - start).num_hours(), 23);
+
+        // 24 exact hours do not
+        let next = DateDuration::parse("24h")
+            .unwrap()
+            .apply(start, zone)
+            .unwrap();
+        assert_eq!((next.datetime - start).num_hours(), 24);
+
+        // month arithmetic clamps to the end of the month
+        let jan = zone
+            .from_local(naive("2024-01-31 08:00:00"))
+            .unwrap()
+            .datetime;
+        let feb = DateDuration::parse("P1M")
+            .unwrap()
+            .apply(jan, zone)
+            .unwrap();
+        assert_eq!(feb.datetime.naive_local(), naive("2024-02-29 08:00:00"));
+
+        // components that parse but do not fit chrono's types, and results
+        // past the last representable date
+        for input in ["9999999999999999d", "4294967296mo", "P300000Y"] {
+            let duration = DateDuration::parse(input).unwrap();
+            assert_eq!(
+                duration.apply(jan, zone),
+                Err(String::from("duration out of range")),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_difference_and_calendar() {
+        let start = DateTime::parse_from_rfc3339("2024-01-31T08:00:00Z").unwrap();
+        let end = DateTime::parse_from_rfc3339("2025-03-02T10:30:15Z").unwrap();
+        let diff = DateDifference::between(start, end);
+        assert_eq!((diff.years, diff.months, diff.days), (1, 1, 2));
+        assert_eq!((diff.hours, diff.minutes, diff.seconds), (2, 30, 15));
+        assert_eq!(diff.human(), "1 year 1 month 2 days 02:30:15");
+        assert!(DateDifference::between(end, start).human().starts_with('-'));
+
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        assert_eq!(business_days(monday, monday + Duration::days(7)), 5);
+        assert_eq!(business_days(monday, monday + Duration::days(12)), 10);
+        assert_eq!(business_days(monday + Duration::days(12), monday), -10);
+
+        let info = CalendarInfo::of(NaiveDate::from_ymd_opt(2024, 12, 30).unwrap());
+        assert_eq!((info.iso_year, info.iso_week), (2025, 1));
+        assert_eq!((info.day_of_year, info.quarter), (365, 4));
+        assert!(info.leap_year);
+        assert_eq!(info.days_in_month, 31);
     }
 }
@@ -0,0 +1,358 @@
+// file and folder comparison
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
+
+use super::HashAlgorithm;
+
+/// How much of a file is inspected when guessing whether it is binary.
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// Files are compared in blocks of this size, never read whole.
+const COMPARE_BLOCK_LEN: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryStatus {
+    OnlyLeft,
+    OnlyRight,
+    Identical,
+    Different,
+    /// Could not be read, see [`FolderEntry::error`].
+    Unreadable,
+}
+
+impl EntryStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EntryStatus::OnlyLeft => "Only left",
+            EntryStatus::OnlyRight => "Only right",
+            EntryStatus::Identical => "Identical",
+            EntryStatus::Different => "Different",
+            EntryStatus::Unreadable => "Unreadable",
+        }
+    }
+}
+
+/// A file found in either folder, `path` is relative to the folder roots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FolderEntry {
+    pub path: PathBuf,
+    pub status: EntryStatus,
+    pub binary: bool,
+    pub error: Option<String>,
+}
+
+impl FolderEntry {
+    fn new(path: &Path, status: EntryStatus, binary: bool) -> FolderEntry {
+        FolderEntry {
+            path: path.to_path_buf(),
+            status,
+            binary,
+            error: None,
+        }
+    }
+
+    fn unreadable(path: &Path, error: String) -> FolderEntry {
+        FolderEntry {
+            error: Some(error),
+            ..FolderEntry::new(path, EntryStatus::Unreadable, false)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileContent {
+    Text(String),
+    Binary { size: usize, hash: String },
+}
+
+/// Files below a folder by relative path, with the error of those that
+/// cannot be compared.
+type FileList = BTreeMap<PathBuf, Option<String>>;
+
+pub struct FolderDifference {}
+
+impl FolderDifference {
+    /// Compares every file below `left` and `right`, matching them by
+    /// relative path and comparing their contents block by block. Only an
+    /// unreadable root fails the whole comparison, other files that cannot
+    /// be read are listed as [`EntryStatus::Unreadable`].
+    pub fn compare(left: &Path, right: &Path) -> Result<Vec<FolderEntry>, String> {
+        let mut left_files = FileList::new();
+        let mut right_files = FileList::new();
+        fs::read_dir(left).map_err(|e| format!("{}: {}", left.display(), e))?;
+        fs::read_dir(right).map_err(|e| format!("{}: {}", right.display(), e))?;
+        Self::collect(left, Path::new(""), &mut left_files);
+        Self::collect(right, Path::new(""), &mut right_files);
+
+        let paths: BTreeSet<&PathBuf> = left_files.keys().chain(right_files.keys()).collect();
+        let entries = paths
+            .into_iter()
+            .map(|path| {
+                let (in_left, in_right) = (left_files.get(path), right_files.get(path));
+                if let Some(error) = [in_left, in_right].into_iter().flatten().flatten().next() {
+                    return FolderEntry::unreadable(path, error.clone());
+                }
+                let entry = match (in_left, in_right) {
+                    (Some(_), None) => Self::sniff(&left.join(path))
+                        .map(|binary| FolderEntry::new(path, EntryStatus::OnlyLeft, binary)),
+                    (None, _) => Self::sniff(&right.join(path))
+                        .map(|binary| FolderEntry::new(path, EntryStatus::OnlyRight, binary)),
+                    (Some(_), Some(_)) => {
+                        let (left, right) = (left.join(path), right.join(path));
+                        Self::same_content(&left, &right).and_then(|same| {
+                            let status = if same {
+                                EntryStatus::Identical
+                            } else {
+                                EntryStatus::Different
+                            };
+                            let binary = Self::sniff(&left)? || Self::sniff(&right)?;
+                            Ok(FolderEntry::new(path, status, binary))
+                        })
+                    }
+                };
+                entry.unwrap_or_else(|e| FolderEntry::unreadable(path, e))
+            })
+            .collect();
+        Ok(entries)
+    }
+
+    /// Lists the files below `root.join(relative)`. Symbolic links to
+    /// folders are not followed, they could loop.
+    fn collect(root: &Path, relative: &Path, files: &mut FileList) {
+        let dir = root.join(relative);
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                files.insert(relative.to_path_buf(), Some(e.to_string()));
+                return;
+            }
+        };
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    files.insert(relative.to_path_buf(), Some(e.to_string()));
+                    continue;
+                }
+            };
+            let path = relative.join(entry.file_name());
+            let file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(e) => {
+                    files.insert(path, Some(e.to_string()));
+                    continue;
+                }
+            };
+            if file_type.is_dir() {
+                Self::collect(root, &path, files);
+            } else if file_type.is_symlink() {
+                let error = match fs::metadata(entry.path()) {
+                    Ok(target) if target.is_dir() => {
+                        Some("link to a folder, not followed".to_string())
+                    }
+                    Ok(_) => None,
+                    Err(e) => Some(format!("broken link: {}", e)),
+                };
+                files.insert(path, error);
+            } else {
+                files.insert(path, None);
+            }
+        }
+    }
+
+    fn read(path: &Path) -> Result<Vec<u8>, String> {
+        fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    fn open(path: &Path) -> Result<File, String> {
+        File::open(path).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Whether the start of a file looks binary.
+    fn sniff(path: &Path) -> Result<bool, String> {
+        let mut head = Vec::with_capacity(BINARY_SNIFF_LEN);
+        Self::open(path)?
+            .take(BINARY_SNIFF_LEN as u64)
+            .read_to_end(&mut head)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(is_binary(&head))
+    }
+
+    /// Compares two files block by block, stopping at the first difference.
+    fn same_content(left: &Path, right: &Path) -> Result<bool, String> {
+        let (mut left_file, mut right_file) = (Self::open(left)?, Self::open(right)?);
+        let len = |file: &File, path: &Path| {
+            file.metadata()
+                .map(|metadata| metadata.len())
+                .map_err(|e| format!("{}: {}", path.display(), e))
+        };
+        if len(&left_file, left)? != len(&right_file, right)? {
+            return Ok(false);
+        }
+
+        let mut left_block = vec![0; COMPARE_BLOCK_LEN];
+        let mut right_block = vec![0; COMPARE_BLOCK_LEN];
+        loop {
+            let read = |file: &mut File, block: &mut [u8], path: &Path| {
+                read_block(file, block).map_err(|e| format!("{}: {}", path.display(), e))
+            };
+            let left_len = read(&mut left_file, &mut left_block, left)?;
+            let right_len = read(&mut right_file, &mut right_block, right)?;
+            if left_block[..left_len] != right_block[..right_len] {
+                return Ok(false);
+            }
+            if left_len == 0 {
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Reads a file as text, or describes it when it is binary. Text that is
+    /// not UTF-8 is decoded lossily.
+    pub fn read_file(path: &Path) -> Result<FileContent, String> {
+        let bytes = Self::read(path)?;
+        if is_binary(&bytes) {
+            return Ok(FileContent::Binary {
+                size: bytes.len(),
+                hash: HashAlgorithm::SHA256.hash_bytes(&bytes),
+            });
+        }
+        Ok(FileContent::Text(
+            String::from_utf8_lossy(&bytes).into_owned(),
+        ))
+    }
+}
+
+/// Fills `block` as far as the file allows, returns how much was read.
+fn read_block(file: &mut File, block: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < block.len() {
+        match file.read(&mut block[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// A folder comparison running on its own thread, so large folders do not
+/// block the UI.
+#[derive(Debug, Clone)]
+pub struct PendingComparison(Arc<OnceLock<Result<Vec<FolderEntry>, String>>>);
+
+impl PendingComparison {
+    /// Starts comparing, `done` is called on the worker thread once the
+    /// result is ready.
+    pub fn start(left: PathBuf, right: PathBuf, done: impl FnOnce() + Send + 'static) -> Self {
+        let result = Arc::new(OnceLock::new());
+        let slot = Arc::clone(&result);
+        std::thread::spawn(move || {
+            let _ = slot.set(FolderDifference::compare(&left, &right));
+            done();
+        });
+        Self(result)
+    }
+
+    pub fn result(&self) -> Option<&Result<Vec<FolderEntry>, String>> {
+        self.0.get()
+    }
+}
+
+impl PartialEq for PendingComparison {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Same heuristic as git: a NUL byte near the start marks the content as
+/// binary.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compare_folders() {
+        let root = std::env::temp_dir().join(format!("folder-differ-{}", std::process::id()));
+        let (left, right) = (root.join("left"), root.join("right"));
+        fs::create_dir_all(left.join("sub")).unwrap();
+        fs::create_dir_all(right.join("sub")).unwrap();
+        fs::write(left.join("same.txt"), "same\n").unwrap();
+        fs::write(right.join("same.txt"), "same\n").unwrap();
+        fs::write(left.join("sub/changed.txt"), "one\n").unwrap();
+        fs::write(right.join("sub/changed.txt"), "two\n").unwrap();
+        fs::write(left.join("left.bin"), [0u8, 1, 2]).unwrap();
+        fs::write(right.join("right.txt"), "new\n").unwrap();
+
+        let entries = FolderDifference::compare(&left, &right).unwrap();
+        let summary: Vec<(String, EntryStatus, bool)> = entries
+            .iter()
+            .map(|e| (e.path.display().to_string(), e.status, e.binary))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("left.bin".to_string(), EntryStatus::OnlyLeft, true),
+                ("right.txt".to_string(), EntryStatus::OnlyRight, false),
+                ("same.txt".to_string(), EntryStatus::Identical, false),
+                (
+                    Path::new("sub").join("changed.txt").display().to_string(),
+                    EntryStatus::Different,
+                    false
+                ),
+            ]
+        );
+
+        assert_eq!(
+            FolderDifference::read_file(&left.join("same.txt")).unwrap(),
+            FileContent::Text("same\n".to_string())
+        );
+        assert!(matches!(
+            FolderDifference::read_file(&left.join("left.bin")).unwrap(),
+            FileContent::Binary { size: 3, .. }
+        ));
+        assert!(FolderDifference::compare(&left, &root.join("missing")).is_err());
+
+        // text that is not UTF-8 is still text
+        fs::write(left.join("latin1.txt"), b"caf\xe9\n").unwrap();
+        assert_eq!(
+            FolderDifference::read_file(&left.join("latin1.txt")).unwrap(),
+            FileContent::Text("caf\u{fffd}\n".to_string())
+        );
+
+        // a broken link is reported on its own entry
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(root.join("nowhere"), left.join("broken")).unwrap();
+            std::os::unix::fs::symlink(&right, right.join("loop")).unwrap();
+            let entries = FolderDifference::compare(&left, &right).unwrap();
+            let status = |name: &str| {
+                let entry = entries.iter().find(|e| e.path == Path::new(name)).unwrap();
+                (entry.status, entry.error.is_some())
+            };
+            assert_eq!(status("broken"), (EntryStatus::Unreadable, true));
+            assert_eq!(status("loop"), (EntryStatus::Unreadable, true));
+            assert_eq!(status("same.txt"), (EntryStatus::Identical, false));
+        }
+
+        // the view compares on a worker thread
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let pending = PendingComparison::start(left.clone(), right.clone(), move || {
+            sender.send(()).unwrap()
+        });
+        receiver.recv().unwrap();
+        assert!(matches!(pending.result(), Some(Ok(entries)) if entries.len() >= 4));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,89 @@
+// fuzzy matching for search boxes
+
+/// Bonus for a match right after the previous one.
+const CONSECUTIVE_BONUS: i32 = 5;
+/// Bonus for a match at the start of a word.
+const WORD_START_BONUS: i32 = 8;
+
+/// Scores `text` against `query`, which has to appear in it as a case
+/// insensitive subsequence. Consecutive matches and matches at word starts
+/// score higher, skipped characters cost a point each. `None` when `query`
+/// does not match at all.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.chars().filter(|c| !c.is_whitespace()) {
+        let found = (position..text.len()).find(|&i| chars_match(wanted, text[i]))?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += CONSECUTIVE_BONUS;
+        } else if let Some(p) = previous {
+            score -= (found - p - 1) as i32;
+        }
+        if is_word_start(&text, found) {
+            score += WORD_START_BONUS;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+/// Keeps the items matching `query`, best first. Ties keep their order.
+pub fn fuzzy_filter<T>(query: &str, items: Vec<T>, key: impl Fn(&T) -> &str) -> Vec<T> {
+    let mut scored: Vec<(i32, T)> = items
+        .into_iter()
+        .filter_map(|item| Some((fuzzy_score(query, key(&item))?, item)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, item)| item).collect()
+}
+
+fn chars_match(wanted: char, c: char) -> bool {
+    wanted == c || wanted.to_lowercase().eq(c.to_lowercase())
+}
+
+fn is_word_start(text: &[char], index: usize) -> bool {
+    let Some(previous) = index.checked_sub(1).map(|i| text[i]) else {
+        return true;
+    };
+    !previous.is_alphanumeric() || (previous.is_lowercase() && text[index].is_uppercase())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert!(fuzzy_score("jsf", "Json Formatter").is_some());
+        assert!(fuzzy_score("xyz", "Json Formatter").is_none());
+        assert!(fuzzy_score("fj", "Json Formatter").is_none());
+
+        // word starts and runs beat scattered letters
+        assert!(fuzzy_score("jf", "Json Formatter") > fuzzy_score("jf", "Jiffy"));
+        assert!(fuzzy_score("form", "Json Formatter") > fuzzy_score("form", "Fast worm"));
+    }
+
+    #[test]
+    fn test_fuzzy_filter() {
+        let items = vec![
+            "Hashing",
+            "Json Formatter",
+            "Sql Formatter",
+            "Text Difference",
+        ];
+        assert_eq!(
+            fuzzy_filter("sqlf", items.clone(), |s| s),
+            ["Sql Formatter"]
+        );
+        assert_eq!(
+            fuzzy_filter("fmt", items.clone(), |s| s),
+            ["Json Formatter", "Sql Formatter"]
+        );
+        assert_eq!(fuzzy_filter("", items.clone(), |s| s), items);
+    }
+}
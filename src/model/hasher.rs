@@ -10,7 +10,7 @@ struct Hasher {
     algorithm: HashAlgorithm,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum HashAlgorithm {
     #[default]
     MD5,
@@ -28,26 +28,47 @@ impl HashAlgorithm {
         DIRECTIONS.iter()
     }
 
+    /// Lowercase name, e.g. `sha256`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            HashAlgorithm::MD5 => "md5",
+            HashAlgorithm::SHA1 => "sha1",
+            HashAlgorithm::SHA256 => "sha256",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::MD5 => "MD5",
+            HashAlgorithm::SHA1 => "SHA1",
+            HashAlgorithm::SHA256 => "SHA256",
+        }
+    }
+
     pub fn hash(&self, input: &str) -> String {
         if input.trim().is_empty() {
             return String::new();
         }
 
-        let text_bytes = input.as_bytes();
+        self.hash_bytes(input.as_bytes())
+    }
+
+    /// Hex digest of raw bytes, used for files.
+    pub fn hash_bytes(&self, bytes: &[u8]) -> String {
         match self {
             HashAlgorithm::MD5 => {
                 let mut md5_hasher = Md5::new();
-                md5_hasher.update(text_bytes);
+                md5_hasher.update(bytes);
                 hex::encode(md5_hasher.finalize())
             }
             HashAlgorithm::SHA1 => {
                 let mut sha1_hasher = Sha1::new();
-                sha1_hasher.update(text_bytes);
+                sha1_hasher.update(bytes);
                 hex::encode(sha1_hasher.finalize())
             }
             HashAlgorithm::SHA256 => {
                 let mut sha256_hasher = Sha256::new();
-                sha256_hasher.update(text_bytes);
+                sha256_hasher.update(bytes);
                 hex::encode(sha256_hasher.finalize())
             }
         }
@@ -1,5 +1,3 @@
-use std::error::Error;
-
 use serde_json::{to_string_pretty, Value};
 
 #[derive(Debug)]
@@ -8,15 +6,31 @@ pub struct JsonFormatter {}
 impl JsonFormatter {
     pub fn pretty_json(text: &str) -> Result<String, &'static str> {
         match serde_json::from_str::<Value>(text) {
-            Ok(json) => match to_string_pretty(&json) {
-                Ok(formatted) => {
-                    return Ok(formatted);
-                }
-                Err(e) => {
-                    return Err("json formatter error");
-                }
-            },
-            Err(_) => return Err("json parse error"),
+            Ok(json) => to_string_pretty(&json).map_err(|_| "json formatter error"),
+            Err(_) => Err("json parse error"),
+        }
+    }
+
+    pub fn minify_json(text: &str) -> Result<String, &'static str> {
+        match serde_json::from_str::<Value>(text) {
+            Ok(json) => serde_json::to_string(&json).map_err(|_| "json formatter error"),
+            Err(_) => Err("json parse error"),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_minify_json() {
+        let pretty = JsonFormatter::pretty_json(r#"{"a": [1, 2], "b": null}"#).unwrap();
+        assert!(pretty.contains('\n'));
+        assert_eq!(
+            JsonFormatter::minify_json(&pretty),
+            Ok(r#"{"a":[1,2],"b":null}"#.to_string())
+        );
+        assert!(JsonFormatter::minify_json("{").is_err());
+    }
+}
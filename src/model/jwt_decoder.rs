@@ -0,0 +1,110 @@
+// JSON Web Token decoding, without signature verification
+
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine as _,
+};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// Registered claims holding a NumericDate (RFC 7519, section 4.1).
+const TIME_CLAIMS: [(&str, &str); 3] = [
+    ("iat", "Issued at"),
+    ("nbf", "Not before"),
+    ("exp", "Expires"),
+];
+
+/// base64url as used by JWS, padding is tolerated.
+const BASE64_URL: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Jwt {
+    pub header: Value,
+    pub payload: Value,
+    /// Still base64url encoded.
+    pub signature: String,
+}
+
+impl Jwt {
+    pub fn decode(token: &str) -> Result<Jwt, String> {
+        let token = token.trim();
+        let token = token.strip_prefix("Bearer ").unwrap_or(token).trim();
+        let parts: Vec<&str> = token.split('.').collect();
+        let [header, payload, signature] = parts[..] else {
+            return Err(format!(
+                "A JWT has 3 parts separated by dots, found {}",
+                parts.len()
+            ));
+        };
+        Ok(Jwt {
+            header: Self::decode_part(header, "header")?,
+            payload: Self::decode_part(payload, "payload")?,
+            signature: signature.to_string(),
+        })
+    }
+
+    fn decode_part(part: &str, name: &str) -> Result<Value, String> {
+        let bytes = BASE64_URL
+            .decode(part)
+            .map_err(|e| format!("Invalid {} encoding: {}", name, e))?;
+        let value: Value = serde_json::from_slice(&bytes)
+            .map_err(|e| format!("The {} is not JSON: {}", name, e))?;
+        if !value.is_object() {
+            return Err(format!("The {} is not a JSON object", name));
+        }
+        Ok(value)
+    }
+
+    pub fn algorithm(&self) -> Option<&str> {
+        self.header.get("alg")?.as_str()
+    }
+
+    /// The `iat`, `nbf` and `exp` claims that are present, with a label.
+    pub fn times(&self) -> Vec<(&'static str, DateTime<Utc>)> {
+        TIME_CLAIMS
+            .iter()
+            .filter_map(|(claim, label)| {
+                let seconds = self.payload.get(claim)?.as_i64()?;
+                Some((*label, DateTime::from_timestamp(seconds, 0)?))
+            })
+            .collect()
+    }
+
+    /// `None` when the token has no `exp` claim.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> Option<bool> {
+        let exp = self.payload.get("exp")?.as_i64()?;
+        Some(now.timestamp() >= exp)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TOKEN: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.\
+        eyJzdWIiOiIxMjM0NTY3ODkwIiwibmFtZSI6IkpvaG4gRG9lIiwiaWF0IjoxNTE2MjM5MDIyfQ.\
+        SflKxwRJSMeKKF2QT4fwpMeJf36POk6yJV_adQssw5c";
+
+    #[test]
+    fn test_decode() {
+        let jwt = Jwt::decode(&format!("Bearer {}", TOKEN)).unwrap();
+        assert_eq!(jwt.algorithm(), Some("HS256"));
+        assert_eq!(jwt.payload["name"], "John Doe");
+        assert_eq!(jwt.signature, "SflKxwRJSMeKKF2QT4fwpMeJf36POk6yJV_adQssw5c");
+        assert_eq!(
+            jwt.times(),
+            [(
+                "Issued at",
+                DateTime::from_timestamp(1516239022, 0).unwrap()
+            )]
+        );
+        assert_eq!(jwt.is_expired(Utc::now()), None);
+
+        assert!(Jwt::decode("a.b").is_err());
+        assert!(Jwt::decode("e30.bm90IGpzb24.sig").is_err());
+    }
+}
@@ -0,0 +1,199 @@
+// three-way text merge
+
+use similar::{capture_diff_slices, Algorithm, DiffTag};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeChunk {
+    /// Text both sides agree on, or that only one side changed.
+    Clean(String),
+    Conflict(MergeConflict),
+}
+
+/// A region both sides changed differently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub base: String,
+    pub ours: String,
+    pub theirs: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictChoice {
+    /// Keeps the conflict markers.
+    #[default]
+    Unresolved,
+    Ours,
+    Theirs,
+    /// Ours followed by theirs.
+    Both,
+}
+
+impl ConflictChoice {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConflictChoice::Unresolved => "Unresolved",
+            ConflictChoice::Ours => "Ours",
+            ConflictChoice::Theirs => "Theirs",
+            ConflictChoice::Both => "Both",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ThreeWayMerge {
+    pub chunks: Vec<MergeChunk>,
+}
+
+impl ThreeWayMerge {
+    /// Line based diff3 merge of `ours` and `theirs`, both derived from `base`.
+    pub fn merge(base: &str, ours: &str, theirs: &str) -> ThreeWayMerge {
+        let base: Vec<&str> = base.split_inclusive('\n').collect();
+        let ours: Vec<&str> = ours.split_inclusive('\n').collect();
+        let theirs: Vec<&str> = theirs.split_inclusive('\n').collect();
+        let ours_match = Self::matches(&base, &ours);
+        let theirs_match = Self::matches(&base, &theirs);
+
+        let mut merge = ThreeWayMerge::default();
+        let (mut b, mut o, mut t) = (0, 0, 0);
+        loop {
+            // stable lines are unchanged on both sides
+            if b < base.len() && ours_match[b] == Some(o) && theirs_match[b] == Some(t) {
+                merge.push_clean(base[b]);
+                b += 1;
+                o += 1;
+                t += 1;
+                continue;
+            }
+
+            let next = (b..base.len()).find_map(|i| Some((i, ours_match[i]?, theirs_match[i]?)));
+            let (b_end, o_end, t_end) = next.unwrap_or((base.len(), ours.len(), theirs.len()));
+            let (base_part, ours_part, theirs_part) =
+                (&base[b..b_end], &ours[o..o_end], &theirs[t..t_end]);
+
+            if ours_part == base_part || ours_part == theirs_part {
+                theirs_part.iter().for_each(|line| merge.push_clean(line));
+            } else if theirs_part == base_part {
+                ours_part.iter().for_each(|line| merge.push_clean(line));
+            } else {
+                merge.chunks.push(MergeChunk::Conflict(MergeConflict {
+                    base: base_part.concat(),
+                    ours: ours_part.concat(),
+                    theirs: theirs_part.concat(),
+                }));
+            }
+
+            if next.is_none() {
+                break;
+            }
+            (b, o, t) = (b_end, o_end, t_end);
+        }
+        merge
+    }
+
+    /// For every line of `base`, the index of the same line in `other`.
+    fn matches(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+        let mut matches = vec![None; base.len()];
+        for op in capture_diff_slices(Algorithm::Myers, base, other) {
+            let (tag, old, new) = op.as_tag_tuple();
+            if tag == DiffTag::Equal {
+                old.zip(new).for_each(|(b, o)| matches[b] = Some(o));
+            }
+        }
+        matches
+    }
+
+    fn push_clean(&mut self, line: &str) {
+        match self.chunks.last_mut() {
+            Some(MergeChunk::Clean(text)) => text.push_str(line),
+            _ => self.chunks.push(MergeChunk::Clean(line.to_string())),
+        }
+    }
+
+    pub fn conflicts(&self) -> impl Iterator<Item = &MergeConflict> {
+        self.chunks.iter().filter_map(|chunk| match chunk {
+            MergeChunk::Conflict(conflict) => Some(conflict),
+            MergeChunk::Clean(_) => None,
+        })
+    }
+
+    /// The merged text. `choices` resolve the conflicts in order, missing or
+    /// unresolved ones get git-style conflict markers.
+    pub fn result(&self, choices: &[ConflictChoice]) -> String {
+        let mut text = String::new();
+        let mut choices = choices.iter();
+        for chunk in &self.chunks {
+            let conflict = match chunk {
+                MergeChunk::Clean(clean) => {
+                    text.push_str(clean);
+                    continue;
+                }
+                MergeChunk::Conflict(conflict) => conflict,
+            };
+            match choices.next().copied().unwrap_or_default() {
+                ConflictChoice::Ours => text.push_str(&conflict.ours),
+                ConflictChoice::Theirs => text.push_str(&conflict.theirs),
+                ConflictChoice::Both => {
+                    Self::push_lines(&mut text, &conflict.ours);
+                    text.push_str(&conflict.theirs);
+                }
+                ConflictChoice::Unresolved => {
+                    text.push_str("<<<<<<< ours\n");
+                    Self::push_lines(&mut text, &conflict.ours);
+                    text.push_str("=======\n");
+                    Self::push_lines(&mut text, &conflict.theirs);
+                    text.push_str(">>>>>>> theirs\n");
+                }
+            }
+        }
+        text
+    }
+
+    /// Appends `lines`, adding the final newline a last line may lack.
+    fn push_lines(text: &mut String, lines: &str) {
+        text.push_str(lines);
+        if !lines.is_empty() && !lines.ends_with('\n') {
+            text.push('\n');
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BASE: &str = "a\nb\nc\nd\ne\n";
+
+    #[test]
+    fn test_clean_merge() {
+        let ours = "A\nb\nc\nd\ne\n";
+        let theirs = "a\nb\nc\nd\nE\nf\n";
+        let merge = ThreeWayMerge::merge(BASE, ours, theirs);
+        assert_eq!(merge.conflicts().count(), 0);
+        assert_eq!(merge.result(&[]), "A\nb\nc\nd\nE\nf\n");
+
+        // the same change on both sides is not a conflict
+        let merge = ThreeWayMerge::merge(BASE, ours, ours);
+        assert_eq!(merge.result(&[]), ours);
+    }
+
+    #[test]
+    fn test_conflict() {
+        let merge = ThreeWayMerge::merge(BASE, "a\nb\nours\nd\ne\n", "a\nb\ntheirs\nd\ne");
+        let conflict = merge.conflicts().next().unwrap();
+        assert_eq!(conflict.base, "c\n");
+        assert_eq!(conflict.ours, "ours\n");
+
+        assert_eq!(
+            merge.result(&[]),
+            "a\nb\n<<<<<<< ours\nours\n=======\ntheirs\n>>>>>>> theirs\nd\ne"
+        );
+        assert_eq!(
+            merge.result(&[ConflictChoice::Theirs]),
+            "a\nb\ntheirs\nd\ne"
+        );
+        assert_eq!(
+            merge.result(&[ConflictChoice::Both]),
+            "a\nb\nours\ntheirs\nd\ne"
+        );
+    }
+}
@@ -1,13 +1,29 @@
+pub mod byte_inspector;
 pub mod calculator;
+pub mod content_detector;
+pub mod cron_parser;
 pub mod datetime_converter;
+pub mod folder_differ;
+pub mod fuzzy_matcher;
 pub mod hasher;
 pub mod json_formatter;
+pub mod jwt_decoder;
+pub mod merger;
 pub mod number_converter;
+pub mod patcher;
 pub mod sql_formatter;
 pub mod text_differ;
+pub use byte_inspector::*;
 pub use calculator::*;
+pub use content_detector::*;
+pub use cron_parser::*;
 pub use datetime_converter::*;
+pub use folder_differ::*;
+pub use fuzzy_matcher::*;
 pub use hasher::*;
 pub use json_formatter::*;
+pub use jwt_decoder::*;
+pub use merger::*;
 pub use number_converter::*;
+pub use patcher::*;
 pub use text_differ::*;
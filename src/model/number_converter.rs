@@ -1,69 +1,534 @@
 use base64::Engine as _;
-use std::{fmt, num::ParseIntError, slice::Iter};
+use half::f16;
+use num_bigint::{BigInt, Sign};
+use num_traits::{One, Signed};
+use serde::{Deserialize, Serialize};
+use std::{fmt, slice::Iter};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+const DIGITS: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+const CROCKFORD: &str = "0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const BASE58_BITCOIN: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE62: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum NumberBaseConverter {
     Binary,
     Octal,
     Decimal,
     Hexadecimal,
     Base64,
+    /// Any radix from 2 to 36 using `0-9a-z`.
+    Radix(u32),
+    Base32Crockford,
+    Base58Bitcoin,
+    Base62,
+}
+
+/// Fixed width used to render negative numbers in two's complement.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum BitWidth {
+    #[default]
+    Unbounded,
+    Bits8,
+    Bits16,
+    Bits32,
+    Bits64,
+    Bits128,
+}
+
+impl BitWidth {
+    pub fn iter() -> Iter<'static, BitWidth> {
+        static WIDTHS: [BitWidth; 6] = [
+            BitWidth::Unbounded,
+            BitWidth::Bits8,
+            BitWidth::Bits16,
+            BitWidth::Bits32,
+            BitWidth::Bits64,
+            BitWidth::Bits128,
+        ];
+        WIDTHS.iter()
+    }
+
+    pub fn bits(&self) -> Option<u32> {
+        match self {
+            BitWidth::Unbounded => None,
+            BitWidth::Bits8 => Some(8),
+            BitWidth::Bits16 => Some(16),
+            BitWidth::Bits32 => Some(32),
+            BitWidth::Bits64 => Some(64),
+            BitWidth::Bits128 => Some(128),
+        }
+    }
+
+    /// The two's complement bit pattern of `num`, as an unsigned value.
+    /// Accepts anything from the signed minimum up to the unsigned maximum.
+    pub fn unsigned(&self, num: &BigInt) -> Result<BigInt, String> {
+        let Some(bits) = self.bits() else {
+            return Ok(num.clone());
+        };
+        let modulus = BigInt::one() << bits;
+        let min = -(BigInt::one() << (bits - 1));
+        if *num < min || *num >= modulus {
+            return Err(format!("{} does not fit in {} bits", num, bits));
+        }
+        Ok(if num.is_negative() {
+            num + modulus
+        } else {
+            num.clone()
+        })
+    }
+
+    /// `num` read back as a signed two's complement value of this width.
+    pub fn signed(&self, num: &BigInt) -> Result<BigInt, String> {
+        let unsigned = self.unsigned(num)?;
+        Ok(match self.bits() {
+            Some(bits) if unsigned >= BigInt::one() << (bits - 1) => {
+                unsigned - (BigInt::one() << bits)
+            }
+            _ => unsigned,
+        })
+    }
+
+    /// Bytes of `num` in the given byte order, padded to the full width.
+    /// Unbounded values use the fewest bytes, negative ones in two's complement.
+    pub fn to_bytes(&self, num: &BigInt, endianness: Endianness) -> Result<Vec<u8>, String> {
+        let value = self.unsigned(num)?;
+        let mut bytes = match self.bits() {
+            Some(bits) => {
+                let (_, bytes) = value.to_bytes_be();
+                let size = (bits / 8) as usize;
+                let mut padded = vec![0; size.saturating_sub(bytes.len())];
+                padded.extend(bytes);
+                padded
+            }
+            // 移除前导零以避免不必要的填充
+            None if value.is_negative() => value.to_signed_bytes_be(),
+            None => value.to_bytes_be().1,
+        };
+        if endianness == Endianness::Little {
+            bytes.reverse();
+        }
+        Ok(bytes)
+    }
+
+    /// Reads the first `bits / 8` bytes as an (unsigned, signed) pair.
+    pub fn from_bytes(&self, bytes: &[u8], endianness: Endianness) -> Option<(BigInt, BigInt)> {
+        let size = (self.bits()? / 8) as usize;
+        let bytes = bytes.get(..size)?;
+        let unsigned = match endianness {
+            Endianness::Big => BigInt::from_bytes_be(Sign::Plus, bytes),
+            Endianness::Little => BigInt::from_bytes_le(Sign::Plus, bytes),
+        };
+        let signed = self.signed(&unsigned).ok()?;
+        Some((unsigned, signed))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum Endianness {
+    #[default]
+    Big,
+    Little,
+}
+
+impl fmt::Display for Endianness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Endianness::Big => write!(f, "Big endian"),
+            Endianness::Little => write!(f, "Little endian"),
+        }
+    }
+}
+
+/// Parses a hex byte string such as `de ad be ef`, `0xDEADBEEF`,
+/// `de:ad:be:ef` or `\xde\xad`.
+pub fn parse_hex_bytes(src: &str) -> Result<Vec<u8>, String> {
+    let src = src.trim();
+    let src = src
+        .strip_prefix("0x")
+        .or_else(|| src.strip_prefix("0X"))
+        .unwrap_or(src);
+    let digits: String = src
+        .replace("\\x", "")
+        .replace("0x", "")
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, ':' | '-' | ','))
+        .collect();
+
+    if digits.is_empty() {
+        return Err(String::from("Please enter some hex bytes"));
+    }
+    if let Some(invalid) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("'{}' is not a hex digit", invalid));
+    }
+    if !digits.len().is_multiple_of(2) {
+        return Err(String::from("Hex bytes need an even number of digits"));
+    }
+    hex::decode(&digits).map_err(|e| e.to_string())
+}
+
+pub fn format_hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FloatFormat {
+    F16,
+    F32,
+    F64,
+}
+
+/// Breakdown of an IEEE-754 value into its raw fields.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct FloatParts {
+    pub negative: bool,
+    /// Biased exponent as stored.
+    pub exponent: u64,
+    pub mantissa: u64,
+    pub value: f64,
+}
+
+impl FloatFormat {
+    pub fn iter() -> Iter<'static, FloatFormat> {
+        static FORMATS: [FloatFormat; 3] = [FloatFormat::F16, FloatFormat::F32, FloatFormat::F64];
+        FORMATS.iter()
+    }
+
+    pub fn bytes(&self) -> usize {
+        match self {
+            FloatFormat::F16 => 2,
+            FloatFormat::F32 => 4,
+            FloatFormat::F64 => 8,
+        }
+    }
+
+    pub fn exponent_bits(&self) -> u32 {
+        match self {
+            FloatFormat::F16 => 5,
+            FloatFormat::F32 => 8,
+            FloatFormat::F64 => 11,
+        }
+    }
+
+    pub fn mantissa_bits(&self) -> u32 {
+        self.bytes() as u32 * 8 - 1 - self.exponent_bits()
+    }
+
+    pub fn bias(&self) -> i64 {
+        (1 << (self.exponent_bits() - 1)) - 1
+    }
+
+    /// Decodes the first `self.bytes()` bytes.
+    pub fn decode(&self, bytes: &[u8], endianness: Endianness) -> Option<FloatParts> {
+        let mut raw = [0u8; 8];
+        let size = self.bytes();
+        raw[8 - size..].copy_from_slice(bytes.get(..size)?);
+        if endianness == Endianness::Little {
+            raw[8 - size..].reverse();
+        }
+        let bits = u64::from_be_bytes(raw);
+
+        let value = match self {
+            FloatFormat::F16 => f16::from_bits(bits as u16).to_f64(),
+            FloatFormat::F32 => f32::from_bits(bits as u32) as f64,
+            FloatFormat::F64 => f64::from_bits(bits),
+        };
+        let mantissa_bits = self.mantissa_bits();
+        Some(FloatParts {
+            negative: bits >> (size * 8 - 1) & 1 == 1,
+            exponent: bits >> mantissa_bits & ((1 << self.exponent_bits()) - 1),
+            mantissa: bits & ((1 << mantissa_bits) - 1),
+            value,
+        })
+    }
+
+    /// Encodes `value`, rounding to the nearest representable value.
+    pub fn encode(&self, value: f64, endianness: Endianness) -> Vec<u8> {
+        let mut bytes = match self {
+            FloatFormat::F16 => f16::from_f64(value).to_be_bytes().to_vec(),
+            FloatFormat::F32 => (value as f32).to_be_bytes().to_vec(),
+            FloatFormat::F64 => value.to_be_bytes().to_vec(),
+        };
+        if endianness == Endianness::Little {
+            bytes.reverse();
+        }
+        bytes
+    }
+}
+
+impl FloatParts {
+    /// `normal`, `subnormal`, `zero`, `infinity` or `NaN`.
+    pub fn class(&self, format: FloatFormat) -> &'static str {
+        let max_exponent = (1 << format.exponent_bits()) - 1;
+        match (self.exponent, self.mantissa) {
+            (0, 0) => "zero",
+            (0, _) => "subnormal",
+            (e, 0) if e == max_exponent => "infinity",
+            (e, _) if e == max_exponent => "NaN",
+            _ => "normal",
+        }
+    }
+
+    /// Exponent with the bias removed, as used in `1.m × 2^e`.
+    pub fn unbiased_exponent(&self, format: FloatFormat) -> i64 {
+        match self.exponent {
+            0 => 1 - format.bias(),
+            e => e as i64 - format.bias(),
+        }
+    }
+}
+
+impl fmt::Display for FloatFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FloatFormat::F16 => write!(f, "f16"),
+            FloatFormat::F32 => write!(f, "f32"),
+            FloatFormat::F64 => write!(f, "f64"),
+        }
+    }
+}
+
+impl fmt::Display for BitWidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.bits() {
+            Some(bits) => write!(f, "{} bit", bits),
+            None => write!(f, "Unbounded"),
+        }
+    }
+}
+
+/// Unbounded values are inspected up to this many bits, and bit fields may
+/// not reach past it.
+pub const MAX_INSPECTED_BITS: u32 = 256;
+
+/// A named range of bits, written as `[3:0]=mode`, `flags[3:0]=mode` or `[7]=enable`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BitField {
+    pub name: String,
+    pub high: u32,
+    pub low: u32,
+}
+
+impl BitField {
+    /// Parses one definition per line (or separated by `;` / `,`).
+    pub fn parse_list(spec: &str) -> Result<Vec<BitField>, String> {
+        spec.split(['\n', ';', ','])
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(BitField::parse)
+            .collect()
+    }
+
+    pub fn parse(line: &str) -> Result<BitField, String> {
+        let invalid = || {
+            format!(
+                "Invalid bit field '{}', expected e.g. flags[3:0]=mode",
+                line
+            )
+        };
+        let (range, name) = line.split_once('=').ok_or_else(invalid)?;
+        let range = range
+            .trim()
+            .strip_suffix(']')
+            .and_then(|r| r.rsplit_once('['))
+            .map(|(_, bits)| bits)
+            .ok_or_else(invalid)?;
+        let (high, low) = match range.split_once(':') {
+            Some((high, low)) => (high.trim(), low.trim()),
+            None => (range.trim(), range.trim()),
+        };
+        let high = high.parse::<u32>().map_err(|_| invalid())?;
+        let low = low.parse::<u32>().map_err(|_| invalid())?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        if high.max(low) >= MAX_INSPECTED_BITS {
+            return Err(format!(
+                "Bit field '{}' reaches past bit {}",
+                line,
+                MAX_INSPECTED_BITS - 1
+            ));
+        }
+
+        Ok(BitField {
+            name: name.to_string(),
+            high: high.max(low),
+            low: high.min(low),
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.high - self.low + 1
+    }
+
+    /// Value of the field within the (non-negative) bit pattern `value`.
+    pub fn extract(&self, value: &BigInt) -> BigInt {
+        let mask = (BigInt::one() << self.width()) - 1;
+        (value >> self.low) & mask
+    }
 }
 
 impl NumberBaseConverter {
     pub fn iter() -> Iter<'static, NumberBaseConverter> {
-        static BASELIST: [NumberBaseConverter; 5] = [
+        static BASELIST: [NumberBaseConverter; 8] = [
             NumberBaseConverter::Binary,
             NumberBaseConverter::Decimal,
             NumberBaseConverter::Hexadecimal,
             NumberBaseConverter::Octal,
             NumberBaseConverter::Base64,
+            NumberBaseConverter::Base32Crockford,
+            NumberBaseConverter::Base58Bitcoin,
+            NumberBaseConverter::Base62,
         ];
         BASELIST.iter()
     }
 
-    pub fn to_decimal(&self, src: &str) -> Result<i64, ParseIntError> {
+    pub fn radix(&self) -> Option<u32> {
         match self {
-            NumberBaseConverter::Binary => i64::from_str_radix(src, 2),
-            NumberBaseConverter::Octal => i64::from_str_radix(src, 8),
-            NumberBaseConverter::Decimal => src.trim().parse::<i64>(),
-            NumberBaseConverter::Hexadecimal => i64::from_str_radix(src, 16),
-            NumberBaseConverter::Base64 => {
-                match base64::engine::general_purpose::STANDARD.decode(src.trim()) {
-                    Ok(bytes) => {
-                        if bytes.len() <= 8 {
-                            let mut value: i64 = 0;
-                            for byte in bytes {
-                                value = (value << 8) | (byte as i64);
-                            }
-                            Ok(value)
-                        } else {
-                            Err(i64::from_str_radix("invalid", 10).unwrap_err())
-                        }
-                    }
-                    Err(_) => Err(i64::from_str_radix("invalid", 10).unwrap_err()),
-                }
+            NumberBaseConverter::Binary => Some(2),
+            NumberBaseConverter::Octal => Some(8),
+            NumberBaseConverter::Decimal => Some(10),
+            NumberBaseConverter::Hexadecimal => Some(16),
+            NumberBaseConverter::Base64 => None,
+            NumberBaseConverter::Radix(radix) => Some(*radix),
+            NumberBaseConverter::Base32Crockford => Some(32),
+            NumberBaseConverter::Base58Bitcoin => Some(58),
+            NumberBaseConverter::Base62 => Some(62),
+        }
+    }
+
+    /// Digits used by this base, most significant digit value last.
+    fn alphabet(&self) -> &'static str {
+        match self {
+            NumberBaseConverter::Base32Crockford => CROCKFORD,
+            NumberBaseConverter::Base58Bitcoin => BASE58_BITCOIN,
+            NumberBaseConverter::Base62 => BASE62,
+            _ => &DIGITS[..self.radix().unwrap_or(10).clamp(2, 36) as usize],
+        }
+    }
+
+    /// Value of a single digit, `None` when it is not part of the alphabet.
+    fn digit_value(&self, c: char) -> Option<u32> {
+        match self {
+            NumberBaseConverter::Base58Bitcoin | NumberBaseConverter::Base62 => {
+                self.alphabet().find(c).map(|i| i as u32)
+            }
+            NumberBaseConverter::Base32Crockford => {
+                // Crockford decoding is case-insensitive and forgiving of look-alikes
+                let c = match c.to_ascii_uppercase() {
+                    'O' => '0',
+                    'I' | 'L' => '1',
+                    c => c,
+                };
+                CROCKFORD.find(c).map(|i| i as u32)
+            }
+            _ => c.to_digit(self.radix().unwrap_or(10)),
+        }
+    }
+
+    fn check_radix(&self) -> Result<u32, String> {
+        match self.radix() {
+            Some(radix) if (2..=62).contains(&radix) && radix as usize <= self.alphabet().len() => {
+                Ok(radix)
             }
+            _ => Err(String::from("Radix must be between 2 and 36")),
         }
     }
 
-    pub fn convert(&self, num: i64) -> Result<String, &'static str> {
+    fn prefix(&self) -> Option<&'static str> {
         match self {
-            NumberBaseConverter::Binary => Ok(format!("{:b}", num)),
-            NumberBaseConverter::Octal => Ok(format!("{:o}", num)),
-            NumberBaseConverter::Decimal => Ok(num.to_string()),
-            NumberBaseConverter::Hexadecimal => Ok(format!("{:x}", num)),
-            NumberBaseConverter::Base64 => {
-                // 移除前导零以避免不必要的填充
-                let bytes: Vec<u8> = num
-                    .to_be_bytes()
-                    .into_iter()
-                    .skip_while(|&x| x == 0)
-                    .collect();
-                let bytes = if bytes.is_empty() { vec![0] } else { bytes };
-                Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+            NumberBaseConverter::Binary => Some("0b"),
+            NumberBaseConverter::Octal => Some("0o"),
+            NumberBaseConverter::Hexadecimal => Some("0x"),
+            _ => None,
+        }
+    }
+
+    /// Parses `src` in this base. Surrounding whitespace, `_` digit separators,
+    /// a leading `-` and the usual `0b`/`0o`/`0x` prefixes are accepted.
+    pub fn to_decimal(&self, src: &str) -> Result<BigInt, String> {
+        let src = src.trim();
+        if src.is_empty() {
+            return Err(String::from("Please enter a number"));
+        }
+
+        if self.radix().is_none() {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(src)
+                .map_err(|e| format!("Invalid base64: {}", e))?;
+            return Ok(BigInt::from_bytes_be(Sign::Plus, &bytes));
+        }
+        let radix = self.check_radix()?;
+
+        let (negative, digits) = match src.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, src.strip_prefix('+').unwrap_or(src)),
+        };
+        let digits = match self.prefix() {
+            Some(prefix) => {
+                let lower = digits.to_ascii_lowercase();
+                if lower.starts_with(prefix) {
+                    &digits[prefix.len()..]
+                } else {
+                    digits
+                }
             }
+            None => digits,
+        };
+        let values = digits
+            .chars()
+            .filter(|c| *c != '_' && !(*self == NumberBaseConverter::Base32Crockford && *c == '-'))
+            .map(|c| {
+                self.digit_value(c)
+                    .map(|v| v as u8)
+                    .ok_or_else(|| format!("'{}' is not a valid {} digit", c, self))
+            })
+            .collect::<Result<Vec<u8>, String>>()?;
+        let value = BigInt::from_radix_be(Sign::Plus, &values, radix)
+            .ok_or_else(|| format!("'{}' is not a valid {} number", src, self))?;
+
+        Ok(if negative { -value } else { value })
+    }
+
+    /// Renders `num` in this base. With a bit width negative values are
+    /// shown as their two's complement pattern, padded to the full width.
+    pub fn convert(&self, num: &BigInt, width: BitWidth) -> Result<String, String> {
+        if *self == NumberBaseConverter::Decimal {
+            return width.signed(num).map(|n| n.to_string());
         }
+
+        if self.radix().is_none() {
+            let bytes = width.to_bytes(num, Endianness::Big)?;
+            return Ok(base64::engine::general_purpose::STANDARD.encode(bytes));
+        }
+
+        let value = width.unsigned(num)?;
+        let radix = self.check_radix()?;
+        let alphabet = self.alphabet().as_bytes();
+        let (_, values) = value.to_radix_be(radix);
+        let digits: String = values
+            .iter()
+            .map(|v| alphabet[*v as usize] as char)
+            .collect();
+        let digits = match width.bits() {
+            // pad only where a digit maps to a whole number of bits
+            Some(bits) if radix.is_power_of_two() => {
+                let bits_per_digit = radix.ilog2();
+                let size = bits.div_ceil(bits_per_digit) as usize;
+                format!("{:0>size$}", digits)
+            }
+            _ => digits,
+        };
+        Ok(if value.is_negative() {
+            format!("-{}", digits)
+        } else {
+            digits
+        })
     }
 }
 
@@ -75,6 +540,10 @@ impl fmt::Display for NumberBaseConverter {
             NumberBaseConverter::Decimal => write!(f, "{:?}(10)", self),
             NumberBaseConverter::Hexadecimal => write!(f, "{:?}(16)", self),
             NumberBaseConverter::Base64 => write!(f, "{:?}(64)", self),
+            NumberBaseConverter::Radix(radix) => write!(f, "Radix({})", radix),
+            NumberBaseConverter::Base32Crockford => write!(f, "{:?}(32)", self),
+            NumberBaseConverter::Base58Bitcoin => write!(f, "{:?}(58)", self),
+            NumberBaseConverter::Base62 => write!(f, "{:?}(62)", self),
         }
 
         // write!(f, "{:?}", self)
@@ -91,4 +560,242 @@ mod test {
         let converter = NumberBaseConverter::Decimal;
         println!("{:?}", converter.to_decimal("36"));
     }
+
+    #[test]
+    fn test_prefixes_and_errors() {
+        let hex = NumberBaseConverter::Hexadecimal;
+        assert_eq!(hex.to_decimal("0xFF").unwrap(), BigInt::from(255));
+        assert_eq!(hex.to_decimal("-0x10").unwrap(), BigInt::from(-16));
+        assert_eq!(
+            NumberBaseConverter::Binary
+                .to_decimal("0b1010_1010")
+                .unwrap(),
+            BigInt::from(170)
+        );
+        assert!(hex.to_decimal("0xZZ").unwrap_err().contains("'Z'"));
+        assert!(NumberBaseConverter::Decimal.to_decimal("").is_err());
+    }
+
+    #[test]
+    fn test_big_integers() {
+        let decimal = NumberBaseConverter::Decimal;
+        let big = decimal
+            .to_decimal("340282366920938463463374607431768211456")
+            .unwrap();
+        assert_eq!(
+            NumberBaseConverter::Hexadecimal
+                .convert(&big, BitWidth::Unbounded)
+                .unwrap(),
+            format!("1{}", "0".repeat(32))
+        );
+        assert_eq!(
+            NumberBaseConverter::Hexadecimal
+                .convert(&BigInt::from(-255), BitWidth::Unbounded)
+                .unwrap(),
+            "-ff"
+        );
+    }
+
+    #[test]
+    fn test_twos_complement() {
+        let minus_one = BigInt::from(-1);
+        let binary = NumberBaseConverter::Binary;
+        let hex = NumberBaseConverter::Hexadecimal;
+        assert_eq!(
+            binary.convert(&minus_one, BitWidth::Bits8).unwrap(),
+            "11111111"
+        );
+        assert_eq!(hex.convert(&minus_one, BitWidth::Bits16).unwrap(), "ffff");
+        assert_eq!(
+            hex.convert(&BigInt::from(5), BitWidth::Bits32).unwrap(),
+            "00000005"
+        );
+        assert_eq!(
+            NumberBaseConverter::Octal
+                .convert(&minus_one, BitWidth::Bits8)
+                .unwrap(),
+            "377"
+        );
+
+        // unsigned input is read back as signed in decimal
+        let byte = hex.to_decimal("0x80").unwrap();
+        assert_eq!(
+            NumberBaseConverter::Decimal
+                .convert(&byte, BitWidth::Bits8)
+                .unwrap(),
+            "-128"
+        );
+        assert!(hex.convert(&BigInt::from(256), BitWidth::Bits8).is_err());
+        assert!(hex.convert(&BigInt::from(-129), BitWidth::Bits8).is_err());
+        assert_eq!(
+            NumberBaseConverter::Base64
+                .convert(&minus_one, BitWidth::Bits16)
+                .unwrap(),
+            "//8="
+        );
+    }
+
+    #[test]
+    fn test_any_radix() {
+        let base36 = NumberBaseConverter::Radix(36);
+        let value = base36.to_decimal("Zz").unwrap();
+        assert_eq!(value, BigInt::from(35 * 36 + 35));
+        assert_eq!(base36.convert(&value, BitWidth::Unbounded).unwrap(), "zz");
+        assert_eq!(
+            NumberBaseConverter::Radix(3)
+                .convert(&BigInt::from(-5), BitWidth::Unbounded)
+                .unwrap(),
+            "-12"
+        );
+        assert_eq!(
+            base36
+                .convert(&BigInt::default(), BitWidth::Unbounded)
+                .unwrap(),
+            "0"
+        );
+        assert!(NumberBaseConverter::Radix(37).to_decimal("1").is_err());
+        assert!(NumberBaseConverter::Radix(1)
+            .convert(&BigInt::from(1), BitWidth::Unbounded)
+            .is_err());
+    }
+
+    #[test]
+    fn test_named_encodings_round_trip() {
+        let value = BigInt::parse_bytes(b"123456789012345678901234567890", 10).unwrap();
+        for converter in [
+            NumberBaseConverter::Base32Crockford,
+            NumberBaseConverter::Base58Bitcoin,
+            NumberBaseConverter::Base62,
+        ] {
+            let encoded = converter.convert(&value, BitWidth::Unbounded).unwrap();
+            assert_eq!(
+                converter.to_decimal(&encoded).unwrap(),
+                value,
+                "{}",
+                converter
+            );
+        }
+
+        let crockford = NumberBaseConverter::Base32Crockford;
+        assert_eq!(
+            crockford
+                .convert(&BigInt::from(1234), BitWidth::Unbounded)
+                .unwrap(),
+            "16J"
+        );
+        assert_eq!(crockford.to_decimal("16j").unwrap(), BigInt::from(1234));
+        assert_eq!(crockford.to_decimal("oI-L").unwrap(), BigInt::from(33));
+        assert_eq!(
+            NumberBaseConverter::Base58Bitcoin
+                .convert(&BigInt::from(57), BitWidth::Unbounded)
+                .unwrap(),
+            "z"
+        );
+        assert!(NumberBaseConverter::Base58Bitcoin
+            .to_decimal("0OIl")
+            .is_err());
+        assert_eq!(
+            NumberBaseConverter::Base62
+                .convert(&BigInt::from(61), BitWidth::Unbounded)
+                .unwrap(),
+            "z"
+        );
+    }
+
+    #[test]
+    fn test_bit_fields() {
+        let fields = BitField::parse_list("flags[3:0]=mode\n[7]=enable; [4:6]=level").unwrap();
+        assert_eq!(
+            fields[0],
+            BitField {
+                name: String::from("mode"),
+                high: 3,
+                low: 0
+            }
+        );
+        assert_eq!((fields[2].high, fields[2].low), (6, 4));
+
+        let value = BigInt::from(0b1101_1010);
+        let decoded: Vec<BigInt> = fields.iter().map(|f| f.extract(&value)).collect();
+        assert_eq!(
+            decoded,
+            [BigInt::from(0b1010), BigInt::from(1), BigInt::from(0b101)]
+        );
+
+        assert!(BitField::parse("mode").is_err());
+        assert!(BitField::parse("[a:0]=mode").is_err());
+        assert!(BitField::parse("[3:0]=").is_err());
+        assert!(BitField::parse("x[4294967295:0]=y").is_err());
+        assert!(BitField::parse("[256]=high").is_err());
+        assert_eq!(BitField::parse("[255:0]=all").unwrap().width(), 256);
+    }
+
+    #[test]
+    fn test_hex_bytes_and_integers() {
+        assert_eq!(
+            parse_hex_bytes("de ad:BE-ef").unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(parse_hex_bytes("0x0102").unwrap(), [1, 2]);
+        assert_eq!(parse_hex_bytes("\\x01\\x02").unwrap(), [1, 2]);
+        assert!(parse_hex_bytes("abc").is_err());
+        assert!(parse_hex_bytes("zz").is_err());
+
+        let bytes = [0xff, 0xfe, 0x00, 0x00];
+        let (unsigned, signed) = BitWidth::Bits16
+            .from_bytes(&bytes, Endianness::Little)
+            .unwrap();
+        assert_eq!(
+            (unsigned, signed),
+            (BigInt::from(0xfeff), BigInt::from(-257))
+        );
+        let (unsigned, _) = BitWidth::Bits32
+            .from_bytes(&bytes, Endianness::Big)
+            .unwrap();
+        assert_eq!(unsigned, BigInt::from(0xfffe0000u32));
+        assert!(BitWidth::Bits64
+            .from_bytes(&bytes, Endianness::Big)
+            .is_none());
+
+        assert_eq!(
+            BitWidth::Bits32
+                .to_bytes(&BigInt::from(-2), Endianness::Little)
+                .unwrap(),
+            [0xfe, 0xff, 0xff, 0xff]
+        );
+        assert_eq!(format_hex_bytes(&[0x0a, 0xff]), "0a ff");
+    }
+
+    #[test]
+    fn test_float_decode_encode() {
+        let parts = FloatFormat::F32
+            .decode(&parse_hex_bytes("c0490fdb").unwrap(), Endianness::Big)
+            .unwrap();
+        assert!(parts.negative);
+        assert_eq!(parts.exponent, 128);
+        assert_eq!(parts.unbiased_exponent(FloatFormat::F32), 1);
+        assert_eq!(parts.mantissa, 0x490fdb);
+        assert_eq!(parts.value as f32, -std::f32::consts::PI);
+        assert_eq!(parts.class(FloatFormat::F32), "normal");
+
+        let parts = FloatFormat::F16
+            .decode(&[0x00, 0x3c], Endianness::Little)
+            .unwrap();
+        assert_eq!(parts.value, 1.0);
+        let parts = FloatFormat::F16
+            .decode(&[0x7c, 0x00], Endianness::Big)
+            .unwrap();
+        assert_eq!(parts.class(FloatFormat::F16), "infinity");
+        let parts = FloatFormat::F64
+            .decode(&[0, 0, 0, 0, 0, 0, 0, 1], Endianness::Big)
+            .unwrap();
+        assert_eq!(parts.class(FloatFormat::F64), "subnormal");
+
+        assert_eq!(FloatFormat::F16.encode(1.0, Endianness::Big), [0x3c, 0x00]);
+        assert_eq!(
+            FloatFormat::F64.encode(1.5, Endianness::Little),
+            [0, 0, 0, 0, 0, 0, 0xf8, 0x3f]
+        );
+        assert!(FloatFormat::F64.decode(&[0; 4], Endianness::Big).is_none());
+    }
 }
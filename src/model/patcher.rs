@@ -0,0 +1,366 @@
+// unified patch parsing and applying
+
+use similar::ChangeTag;
+
+use super::{DiffHunk, DiffLine};
+
+/// A single-file unified diff, as produced by `diff -u` or `git diff`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Patch {
+    pub old_name: String,
+    pub new_name: String,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Where a hunk ended up, relative to the position its header asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppliedHunk {
+    pub index: usize,
+    pub offset: isize,
+    pub fuzz: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PatchResult {
+    pub text: String,
+    pub applied: Vec<AppliedHunk>,
+    /// Indices of hunks that could not be placed.
+    pub rejected: Vec<usize>,
+}
+
+impl Patch {
+    pub fn parse(src: &str) -> Result<Patch, String> {
+        let mut patch = Patch::default();
+        let mut lines = src.lines().peekable();
+
+        while let Some(line) = lines.next() {
+            if let Some(name) = line.strip_prefix("--- ") {
+                if !patch.hunks.is_empty() {
+                    return Err("Patch contains more than one file".to_string());
+                }
+                patch.old_name = Self::file_name(name);
+            } else if let Some(name) = line.strip_prefix("+++ ") {
+                patch.new_name = Self::file_name(name);
+            } else if line.starts_with("@@") {
+                let (old_start, old_len, new_start, new_len) = Self::parse_header(line)
+                    .ok_or_else(|| format!("Invalid hunk header: {}", line))?;
+                let mut hunk = DiffHunk {
+                    old_start,
+                    old_len,
+                    new_start,
+                    new_len,
+                    lines: Vec::new(),
+                };
+
+                let (mut old_index, mut new_index) = (old_start, new_start);
+                while old_index < old_start + old_len || new_index < new_start + new_len {
+                    let Some(line) = lines.next() else {
+                        break;
+                    };
+                    // some tools strip the space of empty context lines
+                    let (tag, text) = match line.chars().next() {
+                        Some('-') => (ChangeTag::Delete, &line[1..]),
+                        Some('+') => (ChangeTag::Insert, &line[1..]),
+                        Some(' ') => (ChangeTag::Equal, &line[1..]),
+                        None => (ChangeTag::Equal, ""),
+                        Some('\\') => {
+                            Self::strip_newline(&mut hunk);
+                            continue;
+                        }
+                        _ => break,
+                    };
+                    let old = (tag != ChangeTag::Insert).then_some(old_index);
+                    let new = (tag != ChangeTag::Delete).then_some(new_index);
+                    old_index += old.is_some() as usize;
+                    new_index += new.is_some() as usize;
+                    hunk.lines.push(DiffLine {
+                        tag,
+                        old_index: old,
+                        new_index: new,
+                        segments: vec![(false, format!("{}\n", text))],
+                    });
+                }
+                if old_index != old_start + old_len || new_index != new_start + new_len {
+                    return Err(format!(
+                        "Hunk {} does not match its header {}",
+                        patch.hunks.len() + 1,
+                        hunk.header()
+                    ));
+                }
+
+                // `\ No newline at end of file` belongs to the line before it
+                if lines.peek().is_some_and(|l| l.starts_with('\\')) {
+                    lines.next();
+                    Self::strip_newline(&mut hunk);
+                }
+                patch.hunks.push(hunk);
+            }
+        }
+
+        if patch.hunks.is_empty() {
+            return Err("No hunks found".to_string());
+        }
+        Ok(patch)
+    }
+
+    /// Handles `\ No newline at end of file`, which is about the line before
+    /// it, in the middle of a hunk or at its end.
+    fn strip_newline(hunk: &mut DiffHunk) {
+        if let Some((_, text)) = hunk.lines.last_mut().and_then(|l| l.segments.last_mut()) {
+            if text.ends_with('\n') {
+                text.pop();
+            }
+        }
+    }
+
+    /// Strips the timestamp and the `a/` `b/` prefixes git adds.
+    fn file_name(name: &str) -> String {
+        let name = name.split('\t').next().unwrap_or_default().trim();
+        name.strip_prefix("a/")
+            .or_else(|| name.strip_prefix("b/"))
+            .unwrap_or(name)
+            .to_string()
+    }
+
+    /// Parses `@@ -l,s +l,s @@` into 0-based starts and lengths.
+    fn parse_header(line: &str) -> Option<(usize, usize, usize, usize)> {
+        let mut parts = line.strip_prefix("@@ ")?.split_whitespace();
+        let range = |part: Option<&str>, sign: char| -> Option<(usize, usize)> {
+            let part = part?.strip_prefix(sign)?;
+            let (start, len) = match part.split_once(',') {
+                Some((start, len)) => (start.parse::<usize>().ok()?, len.parse().ok()?),
+                None => (part.parse().ok()?, 1),
+            };
+            // empty ranges point at the line before them
+            Some((
+                if len == 0 {
+                    start
+                } else {
+                    start.checked_sub(1)?
+                },
+                len,
+            ))
+        };
+        let (old_start, old_len) = range(parts.next(), '-')?;
+        let (new_start, new_len) = range(parts.next(), '+')?;
+        Some((old_start, old_len, new_start, new_len))
+    }
+
+    /// The patch that undoes this one.
+    pub fn reverse(&self) -> Patch {
+        let hunks = self
+            .hunks
+            .iter()
+            .map(|hunk| DiffHunk {
+                old_start: hunk.new_start,
+                old_len: hunk.new_len,
+                new_start: hunk.old_start,
+                new_len: hunk.old_len,
+                lines: hunk
+                    .lines
+                    .iter()
+                    .map(|line| DiffLine {
+                        tag: match line.tag {
+                            ChangeTag::Delete => ChangeTag::Insert,
+                            ChangeTag::Insert => ChangeTag::Delete,
+                            ChangeTag::Equal => ChangeTag::Equal,
+                        },
+                        old_index: line.new_index,
+                        new_index: line.old_index,
+                        segments: line.segments.clone(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        Patch {
+            old_name: self.new_name.clone(),
+            new_name: self.old_name.clone(),
+            hunks,
+        }
+    }
+
+    /// Applies the hunks in order. A hunk may move by any offset from the
+    /// position in its header, and with `max_fuzz` up to that many context
+    /// lines at either end may be ignored, like `patch --fuzz`.
+    pub fn apply(&self, original: &str, max_fuzz: usize) -> PatchResult {
+        let source: Vec<&str> = original.split_inclusive('\n').collect();
+        let mut result = PatchResult::default();
+        let mut cursor = 0;
+        let mut offset: isize = 0;
+
+        for (index, hunk) in self.hunks.iter().enumerate() {
+            let expected = (hunk.old_start as isize + offset).max(0) as usize;
+            let Some((position, fuzz, skip_front, skip_back)) =
+                Self::locate(hunk, &source, cursor, expected, max_fuzz)
+            else {
+                result.rejected.push(index);
+                continue;
+            };
+
+            result.text.extend(source[cursor..position].iter().copied());
+            // dropped context lines are the first and last lines of the hunk
+            let kept = &hunk.lines[skip_front..hunk.lines.len() - skip_back];
+            let mut source_line = position;
+            for line in kept {
+                match line.tag {
+                    // context comes from the original so fuzzy matches keep it
+                    ChangeTag::Equal => {
+                        result.text.push_str(source[source_line]);
+                        source_line += 1;
+                    }
+                    ChangeTag::Delete => source_line += 1,
+                    ChangeTag::Insert => result.text.push_str(&line.text()),
+                }
+            }
+
+            cursor = source_line;
+            offset = position as isize - skip_front as isize - hunk.old_start as isize;
+            result.applied.push(AppliedHunk {
+                index,
+                offset,
+                fuzz,
+            });
+        }
+
+        result.text.extend(source[cursor..].iter().copied());
+        result
+    }
+
+    /// Finds where the hunk's old lines match, nearest to `expected` first.
+    /// Returns the start of the matched lines, the fuzz used and how many
+    /// context lines were dropped at the front and back.
+    fn locate(
+        hunk: &DiffHunk,
+        source: &[&str],
+        cursor: usize,
+        expected: usize,
+        max_fuzz: usize,
+    ) -> Option<(usize, usize, usize, usize)> {
+        let old_lines: Vec<String> = hunk
+            .lines
+            .iter()
+            .filter(|l| l.old_index.is_some())
+            .map(|l| l.text())
+            .collect();
+        let leading = hunk
+            .lines
+            .iter()
+            .take_while(|l| l.tag == ChangeTag::Equal)
+            .count();
+        let trailing = hunk
+            .lines
+            .iter()
+            .rev()
+            .take_while(|l| l.tag == ChangeTag::Equal)
+            .count();
+
+        for fuzz in 0..=max_fuzz {
+            let front = fuzz.min(leading);
+            let back = fuzz.min(trailing);
+            if fuzz > 0 && front + back == 0 {
+                break;
+            }
+            if front + back > old_lines.len() {
+                continue;
+            }
+            let needle = &old_lines[front..old_lines.len() - back];
+            if needle.len() > source.len().saturating_sub(cursor) {
+                continue;
+            }
+            let last = source.len() - needle.len();
+            let expected = (expected + front).clamp(cursor, last);
+
+            let matches = |start: usize| {
+                needle.iter().zip(&source[start..]).all(|(a, b)| {
+                    a.trim_end_matches(['\r', '\n']) == b.trim_end_matches(['\r', '\n'])
+                })
+            };
+            for distance in 0..=last.max(expected) {
+                let candidates = [
+                    expected.checked_add(distance),
+                    expected.checked_sub(distance),
+                ];
+                for start in candidates.into_iter().flatten() {
+                    if start >= cursor && start <= last && matches(start) {
+                        return Some((start, fuzz, front, back));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::model::{DiffOptions, TextDifference};
+
+    use super::*;
+
+    const ORIGINAL: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n";
+
+    #[test]
+    fn test_round_trip() {
+        let modified = "zero\none\ntwo\n3\nfour\nfive\nsix\nseven\neight\nnine\nten";
+        let unified =
+            TextDifference::unified(ORIGINAL, modified, &DiffOptions::default(), 1, "a", "b");
+        let patch = Patch::parse(&unified).unwrap();
+        assert_eq!(patch.hunks.len(), 2);
+
+        let result = patch.apply(ORIGINAL, 0);
+        assert!(result.rejected.is_empty());
+        assert_eq!(result.text, modified);
+        assert_eq!(patch.reverse().apply(modified, 0).text, ORIGINAL);
+    }
+
+    #[test]
+    fn test_no_newline_marker() {
+        // the old file lacked the final newline, the new one has it
+        let patch =
+            Patch::parse("@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+c\n").unwrap();
+        let result = patch.apply("a\nb", 0);
+        assert!(result.rejected.is_empty());
+        assert_eq!(result.text, "a\nc\n");
+        assert_eq!(patch.reverse().apply(&result.text, 0).text, "a\nb");
+    }
+
+    #[test]
+    fn test_git_patch_with_offset_and_fuzz() {
+        let git = "diff --git a/list.txt b/list.txt\n\
+                   index 83db48f..bf269f4 100644\n\
+                   --- a/list.txt\n\
+                   +++ b/list.txt\n\
+                   @@ -2,3 +2,3 @@ header\n \
+                   two\n\
+                   -three\n\
+                   +THREE\n \
+                   four\n";
+        let patch = Patch::parse(git).unwrap();
+        assert_eq!(patch.old_name, "list.txt");
+        assert_eq!(patch.hunks[0].old_start, 1);
+
+        // two lines were added at the top since the patch was made
+        let shifted = format!("a\nb\n{}", ORIGINAL);
+        let result = patch.apply(&shifted, 0);
+        assert_eq!(result.applied[0].offset, 2);
+        assert!(result.text.contains("two\nTHREE\nfour"));
+
+        // the leading context line changed, only fuzz can place the hunk
+        let changed = ORIGINAL.replace("two", "2");
+        assert_eq!(patch.apply(&changed, 0).rejected, [0]);
+        let result = patch.apply(&changed, 1);
+        assert_eq!(result.applied[0].fuzz, 1);
+        assert_eq!(result.text, changed.replace("three", "THREE"));
+    }
+
+    #[test]
+    fn test_rejected_and_invalid() {
+        let patch = Patch::parse("@@ -1 +1 @@\n-missing\n+found\n").unwrap();
+        let result = patch.apply(ORIGINAL, 2);
+        assert_eq!(result.rejected, [0]);
+        assert_eq!(result.text, ORIGINAL);
+
+        assert!(Patch::parse("just text").is_err());
+        assert!(Patch::parse("@@ -1,3 +1,3 @@\n one\n").is_err());
+    }
+}
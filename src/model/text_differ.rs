@@ -1,9 +1,195 @@
 // text difference
 
-use similar::{ChangeTag, TextDiff};
+use std::{ops::Range, slice::Iter};
+
+use serde::{Deserialize, Serialize};
+use similar::{capture_diff_slices, Algorithm, ChangeTag, DiffTag, TextDiff};
 
 pub struct TextDifference {}
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DiffGranularity {
+    #[default]
+    Line,
+    Word,
+    Character,
+}
+
+impl DiffGranularity {
+    pub fn iter() -> Iter<'static, DiffGranularity> {
+        static GRANULARITY: [DiffGranularity; 3] = [
+            DiffGranularity::Line,
+            DiffGranularity::Word,
+            DiffGranularity::Character,
+        ];
+        GRANULARITY.iter()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiffGranularity::Line => "Line",
+            DiffGranularity::Word => "Word",
+            DiffGranularity::Character => "Character",
+        }
+    }
+}
+
+/// `(emphasized, text)` parts of a line.
+pub type Segments = Vec<(bool, String)>;
+
+/// One line of a line diff. `segments` split the line into `(emphasized, text)`
+/// parts, where emphasized parts are the words that changed inside the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub tag: ChangeTag,
+    pub old_index: Option<usize>,
+    pub new_index: Option<usize>,
+    pub segments: Segments,
+}
+
+impl DiffLine {
+    pub fn text(&self) -> String {
+        self.segments.iter().map(|(_, s)| s.as_str()).collect()
+    }
+
+    pub fn sign(&self) -> &'static str {
+        match self.tag {
+            ChangeTag::Delete => "-",
+            ChangeTag::Insert => "+",
+            ChangeTag::Equal => " ",
+        }
+    }
+}
+
+/// What [`TextDifference::lines`] ignores when comparing lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffOptions {
+    /// Treat runs of whitespace as one space and ignore trailing whitespace.
+    pub ignore_whitespace_change: bool,
+    pub ignore_all_whitespace: bool,
+    pub ignore_blank_lines: bool,
+    pub ignore_case: bool,
+    /// Treat `\r\n`, `\n` and a missing final newline alike.
+    pub ignore_line_endings: bool,
+}
+
+impl DiffOptions {
+    /// Comparison key of a line.
+    fn normalize(&self, line: &str) -> String {
+        let body = line.trim_end_matches(['\r', '\n']);
+        let ending = &line[body.len()..];
+
+        let mut key = if self.ignore_all_whitespace {
+            body.chars().filter(|c| !c.is_whitespace()).collect()
+        } else if self.ignore_whitespace_change {
+            let mut key = String::with_capacity(body.len());
+            let mut space = false;
+            for c in body.chars() {
+                if c.is_whitespace() {
+                    space = true;
+                } else {
+                    if space {
+                        key.push(' ');
+                    }
+                    space = false;
+                    key.push(c);
+                }
+            }
+            key
+        } else {
+            body.to_string()
+        };
+        if self.ignore_case {
+            key = key.to_lowercase();
+        }
+        if !self.ignore_line_endings {
+            key.push_str(ending);
+        }
+        key
+    }
+}
+
+/// A group of changed lines with their surrounding context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// 0-based start and length of the hunk in the original text.
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+impl DiffHunk {
+    /// `@@ -l,s +l,s @@` header in unified diff notation.
+    pub fn header(&self) -> String {
+        let range = |start: usize, len: usize| match len {
+            0 => format!("{},0", start),
+            1 => format!("{}", start + 1),
+            _ => format!("{},{}", start + 1, len),
+        };
+        format!(
+            "@@ -{} +{} @@",
+            range(self.old_start, self.old_len),
+            range(self.new_start, self.new_len)
+        )
+    }
+}
+
+/// Summary of a line diff.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DiffStats {
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Lines replaced by another line, counted once per pair.
+    pub changed: usize,
+    /// Number of separate change blocks.
+    pub blocks: usize,
+    /// `2 * matching / total` lines, the same measure as `TextDiff::ratio`.
+    pub similarity: f32,
+}
+
+impl DiffStats {
+    pub fn of(lines: &[DiffLine]) -> DiffStats {
+        let count = |tag: ChangeTag| lines.iter().filter(|l| l.tag == tag).count();
+        let blocks = TextDifference::blocks(lines);
+        let changed = blocks
+            .iter()
+            .map(|block| {
+                let lines = &lines[block.clone()];
+                let deleted = lines.iter().filter(|l| l.tag == ChangeTag::Delete).count();
+                deleted.min(lines.len() - deleted)
+            })
+            .sum();
+
+        let matching = lines
+            .iter()
+            .filter(|l| l.old_index.is_some() && l.new_index.is_some())
+            .count();
+        let total = lines.iter().filter(|l| l.old_index.is_some()).count()
+            + lines.iter().filter(|l| l.new_index.is_some()).count();
+        DiffStats {
+            insertions: count(ChangeTag::Insert),
+            deletions: count(ChangeTag::Delete),
+            changed,
+            blocks: blocks.len(),
+            similarity: if total == 0 {
+                1.0
+            } else {
+                2.0 * matching as f32 / total as f32
+            },
+        }
+    }
+}
+
+/// A row of a side-by-side view, either side may be empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffRow {
+    pub old: Option<DiffLine>,
+    pub new: Option<DiffLine>,
+}
+
 impl TextDifference {
     pub fn differ(text1: &str, text2: &str) -> Vec<(String, String)> {
         let diff = TextDiff::from_words(text1, text2);
@@ -19,6 +205,357 @@ impl TextDifference {
             })
             .collect()
     }
+
+    /// Flat list of changes at word or character granularity. Line
+    /// granularity is served by [`TextDifference::lines`].
+    pub fn changes(
+        text1: &str,
+        text2: &str,
+        granularity: DiffGranularity,
+    ) -> Vec<(ChangeTag, String)> {
+        let diff = match granularity {
+            DiffGranularity::Line => TextDiff::from_lines(text1, text2),
+            DiffGranularity::Word => TextDiff::from_words(text1, text2),
+            DiffGranularity::Character => TextDiff::from_chars(text1, text2),
+        };
+
+        let mut changes: Vec<(ChangeTag, String)> = Vec::new();
+        for change in diff.iter_all_changes() {
+            // merge runs so the view gets one span per change
+            match changes.last_mut() {
+                Some((tag, text)) if *tag == change.tag() => {
+                    text.push_str(&change.to_string_lossy())
+                }
+                _ => changes.push((change.tag(), change.to_string_lossy().into_owned())),
+            }
+        }
+        changes
+    }
+
+    /// Line diff with the changed words inside modified lines emphasized.
+    ///
+    /// Lines are compared after normalization by `options` but keep their
+    /// original text. Lines that only differ by ignored changes are reported
+    /// as [`ChangeTag::Equal`] on one side each, so both versions stay visible.
+    pub fn lines(text1: &str, text2: &str, options: &DiffOptions) -> Vec<DiffLine> {
+        let old: Vec<&str> = text1.split_inclusive('\n').collect();
+        let new: Vec<&str> = text2.split_inclusive('\n').collect();
+
+        // indices of the lines taking part in the comparison
+        let keep = |lines: &[&str]| -> Vec<usize> {
+            (0..lines.len())
+                .filter(|&i| !(options.ignore_blank_lines && lines[i].trim().is_empty()))
+                .collect()
+        };
+        let old_kept = keep(&old);
+        let new_kept = keep(&new);
+        let old_keys: Vec<String> = old_kept
+            .iter()
+            .map(|&i| options.normalize(old[i]))
+            .collect();
+        let new_keys: Vec<String> = new_kept
+            .iter()
+            .map(|&i| options.normalize(new[i]))
+            .collect();
+
+        let mut builder = LineBuilder {
+            old: &old,
+            new: &new,
+            old_next: 0,
+            new_next: 0,
+            lines: Vec::new(),
+        };
+        for op in capture_diff_slices(Algorithm::Myers, &old_keys, &new_keys) {
+            let (tag, old_range, new_range) = op.as_tag_tuple();
+            let old_lines: Vec<usize> = old_range.map(|i| old_kept[i]).collect();
+            let new_lines: Vec<usize> = new_range.map(|i| new_kept[i]).collect();
+            match tag {
+                DiffTag::Equal => {
+                    for (o, n) in old_lines.into_iter().zip(new_lines) {
+                        builder.equal(o, n);
+                    }
+                }
+                DiffTag::Delete => old_lines.into_iter().for_each(|o| builder.delete(o, None)),
+                DiffTag::Insert => new_lines.into_iter().for_each(|n| builder.insert(n, None)),
+                DiffTag::Replace => {
+                    // pair lines up for word level emphasis
+                    let (old_emphasis, new_emphasis): (Vec<_>, Vec<_>) = old_lines
+                        .iter()
+                        .zip(&new_lines)
+                        .map(|(&o, &n)| word_emphasis(old[o], new[n]))
+                        .unzip();
+                    let mut old_emphasis = old_emphasis.into_iter();
+                    for o in old_lines {
+                        builder.delete(o, old_emphasis.next());
+                    }
+                    let mut new_emphasis = new_emphasis.into_iter();
+                    for n in new_lines {
+                        builder.insert(n, new_emphasis.next());
+                    }
+                }
+            }
+        }
+        builder.skipped(old.len(), new.len());
+        builder.lines
+    }
+
+    /// Ranges of consecutive changed lines.
+    pub fn blocks(lines: &[DiffLine]) -> Vec<Range<usize>> {
+        let mut blocks: Vec<Range<usize>> = Vec::new();
+        for (index, line) in lines.iter().enumerate() {
+            if line.tag == ChangeTag::Equal {
+                continue;
+            }
+            match blocks.last_mut() {
+                Some(block) if block.end == index => block.end += 1,
+                _ => blocks.push(index..index + 1),
+            }
+        }
+        blocks
+    }
+
+    /// Similarity of the texts at the given granularity, from 0 to 1.
+    pub fn ratio(text1: &str, text2: &str, granularity: DiffGranularity) -> f32 {
+        match granularity {
+            DiffGranularity::Line => TextDiff::from_lines(text1, text2).ratio(),
+            DiffGranularity::Word => TextDiff::from_words(text1, text2).ratio(),
+            DiffGranularity::Character => TextDiff::from_chars(text1, text2).ratio(),
+        }
+    }
+
+    /// Groups changed lines into hunks with `context` surrounding lines.
+    pub fn hunks(lines: &[DiffLine], context: usize) -> Vec<DiffHunk> {
+        let changed: Vec<usize> = (0..lines.len())
+            .filter(|&i| lines[i].tag != ChangeTag::Equal)
+            .collect();
+        let Some(&first) = changed.first() else {
+            return Vec::new();
+        };
+
+        let mut ranges = Vec::new();
+        let (mut start, mut last) = (first, first);
+        for &index in &changed[1..] {
+            if index - last - 1 > 2 * context {
+                ranges.push((start, last));
+                start = index;
+            }
+            last = index;
+        }
+        ranges.push((start, last));
+
+        ranges
+            .into_iter()
+            .map(|(start, last)| {
+                let start = start.saturating_sub(context);
+                let end = (last + context + 1).min(lines.len());
+                let hunk = &lines[start..end];
+                let before = &lines[..start];
+                DiffHunk {
+                    old_start: before.iter().filter(|l| l.old_index.is_some()).count(),
+                    old_len: hunk.iter().filter(|l| l.old_index.is_some()).count(),
+                    new_start: before.iter().filter(|l| l.new_index.is_some()).count(),
+                    new_len: hunk.iter().filter(|l| l.new_index.is_some()).count(),
+                    lines: hunk.to_vec(),
+                }
+            })
+            .collect()
+    }
+
+    /// Pairs the one-sided lines of the same change block into rows.
+    pub fn side_by_side(lines: &[DiffLine]) -> Vec<DiffRow> {
+        let mut rows = Vec::new();
+        let mut deleted: Vec<DiffLine> = Vec::new();
+        let mut inserted: Vec<DiffLine> = Vec::new();
+
+        for line in lines {
+            match (line.old_index, line.new_index) {
+                (Some(_), Some(_)) => {
+                    Self::flush_block(&mut rows, &mut deleted, &mut inserted);
+                    rows.push(DiffRow {
+                        old: Some(line.clone()),
+                        new: Some(line.clone()),
+                    });
+                }
+                (Some(_), None) => {
+                    if !inserted.is_empty() {
+                        Self::flush_block(&mut rows, &mut deleted, &mut inserted);
+                    }
+                    deleted.push(line.clone());
+                }
+                _ => inserted.push(line.clone()),
+            }
+        }
+        Self::flush_block(&mut rows, &mut deleted, &mut inserted);
+        rows
+    }
+
+    fn flush_block(
+        rows: &mut Vec<DiffRow>,
+        deleted: &mut Vec<DiffLine>,
+        inserted: &mut Vec<DiffLine>,
+    ) {
+        let len = deleted.len().max(inserted.len());
+        let mut old = deleted.drain(..);
+        let mut new = inserted.drain(..);
+        for _ in 0..len {
+            rows.push(DiffRow {
+                old: old.next(),
+                new: new.next(),
+            });
+        }
+    }
+
+    /// Standard unified diff, as produced by `diff -u`. Empty when the texts
+    /// are equal under `options`.
+    pub fn unified(
+        text1: &str,
+        text2: &str,
+        options: &DiffOptions,
+        context: usize,
+        old_name: &str,
+        new_name: &str,
+    ) -> String {
+        let lines = Self::lines(text1, text2, options);
+        let hunks = Self::hunks(&lines, context);
+        if hunks.is_empty() {
+            return String::new();
+        }
+
+        let mut patch = format!("--- {}\n+++ {}\n", old_name, new_name);
+        for hunk in hunks {
+            patch.push_str(&hunk.header());
+            patch.push('\n');
+            for line in &hunk.lines {
+                let sign = match (line.old_index, line.new_index) {
+                    (Some(_), Some(_)) => " ",
+                    (Some(_), None) => "-",
+                    _ => "+",
+                };
+                patch.push_str(sign);
+                patch.push_str(&line.text());
+                if !line.text().ends_with('\n') {
+                    patch.push_str("\n\\ No newline at end of file\n");
+                }
+            }
+        }
+        patch
+    }
+}
+
+/// Emphasized `(old, new)` segments of two versions of a line.
+fn word_emphasis(old: &str, new: &str) -> (Segments, Segments) {
+    let diff = TextDiff::from_words(old, new);
+    let mut old_segments = Segments::new();
+    let mut new_segments = Segments::new();
+    for change in diff.iter_all_changes() {
+        let text = change.to_string_lossy();
+        let (segments, emphasized) = match change.tag() {
+            ChangeTag::Delete => (&mut old_segments, true),
+            ChangeTag::Insert => (&mut new_segments, true),
+            ChangeTag::Equal => {
+                push_segment(&mut new_segments, false, &text);
+                (&mut old_segments, false)
+            }
+        };
+        push_segment(segments, emphasized, &text);
+    }
+    (old_segments, new_segments)
+}
+
+fn push_segment(segments: &mut Segments, emphasized: bool, text: &str) {
+    match segments.last_mut() {
+        Some((last, value)) if *last == emphasized => value.push_str(text),
+        _ => segments.push((emphasized, text.to_string())),
+    }
+}
+
+/// Collects [`DiffLine`]s, re-inserting lines that were left out of the
+/// comparison in their original position.
+struct LineBuilder<'a> {
+    old: &'a [&'a str],
+    new: &'a [&'a str],
+    old_next: usize,
+    new_next: usize,
+    lines: Vec<DiffLine>,
+}
+
+impl LineBuilder<'_> {
+    fn line(
+        tag: ChangeTag,
+        old_index: Option<usize>,
+        new_index: Option<usize>,
+        text: &str,
+    ) -> DiffLine {
+        DiffLine {
+            tag,
+            old_index,
+            new_index,
+            segments: vec![(false, text.to_string())],
+        }
+    }
+
+    /// Emits the ignored lines before `old` and `new` as one-sided equal lines.
+    fn skipped(&mut self, old: usize, new: usize) {
+        while self.old_next < old {
+            let line = Self::line(
+                ChangeTag::Equal,
+                Some(self.old_next),
+                None,
+                self.old[self.old_next],
+            );
+            self.lines.push(line);
+            self.old_next += 1;
+        }
+        while self.new_next < new {
+            let line = Self::line(
+                ChangeTag::Equal,
+                None,
+                Some(self.new_next),
+                self.new[self.new_next],
+            );
+            self.lines.push(line);
+            self.new_next += 1;
+        }
+    }
+
+    fn equal(&mut self, old: usize, new: usize) {
+        self.skipped(old, new);
+        if self.old[old] == self.new[new] {
+            self.lines.push(Self::line(
+                ChangeTag::Equal,
+                Some(old),
+                Some(new),
+                self.old[old],
+            ));
+        } else {
+            self.lines
+                .push(Self::line(ChangeTag::Equal, Some(old), None, self.old[old]));
+            self.lines
+                .push(Self::line(ChangeTag::Equal, None, Some(new), self.new[new]));
+        }
+        self.old_next = old + 1;
+        self.new_next = new + 1;
+    }
+
+    fn delete(&mut self, old: usize, segments: Option<Segments>) {
+        self.skipped(old, self.new_next);
+        let mut line = Self::line(ChangeTag::Delete, Some(old), None, self.old[old]);
+        if let Some(segments) = segments {
+            line.segments = segments;
+        }
+        self.lines.push(line);
+        self.old_next = old + 1;
+    }
+
+    fn insert(&mut self, new: usize, segments: Option<Segments>) {
+        self.skipped(self.old_next, new);
+        let mut line = Self::line(ChangeTag::Insert, None, Some(new), self.new[new]);
+        if let Some(segments) = segments {
+            line.segments = segments;
+        }
+        self.lines.push(line);
+        self.new_next = new + 1;
+    }
 }
 
 #[cfg(test)]
@@ -26,6 +563,8 @@ mod test {
 
     use similar::{ChangeTag, TextDiff};
 
+    use super::*;
+
     #[test]
     fn test_diff() {
         let diff = TextDiff::from_lines(
@@ -49,4 +588,146 @@ mod test {
             print!("{}{}", sign, change);
         }
     }
+
+    #[test]
+    fn test_lines_and_side_by_side() {
+        let lines = TextDifference::lines(
+            "a\nold line\nc\n",
+            "a\nnew line\nextra\nc\n",
+            &DiffOptions::default(),
+        );
+        let signs: String = lines.iter().map(|l| l.sign()).collect();
+        assert_eq!(signs, " -++ ");
+        assert_eq!(lines[1].text(), "old line\n");
+        assert!(lines[1].segments.contains(&(true, String::from("old"))));
+        assert_eq!(lines[2].new_index, Some(1));
+
+        let rows = TextDifference::side_by_side(&lines);
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1].old.as_ref().unwrap().text(), "old line\n");
+        assert_eq!(rows[1].new.as_ref().unwrap().text(), "new line\n");
+        assert!(rows[2].old.is_none());
+    }
+
+    #[test]
+    fn test_changes_granularity() {
+        let words = TextDifference::changes("one two", "one three", DiffGranularity::Word);
+        assert_eq!(
+            words,
+            [
+                (ChangeTag::Equal, String::from("one ")),
+                (ChangeTag::Delete, String::from("two")),
+                (ChangeTag::Insert, String::from("three")),
+            ]
+        );
+        let chars = TextDifference::changes("abc", "abd", DiffGranularity::Character);
+        assert_eq!(chars[0], (ChangeTag::Equal, String::from("ab")));
+    }
+
+    #[test]
+    fn test_unified() {
+        let patch = TextDifference::unified(
+            "a\nb\nc\n",
+            "a\nB\nc\n",
+            &DiffOptions::default(),
+            1,
+            "a.txt",
+            "b.txt",
+        );
+        assert_eq!(
+            patch,
+            "--- a.txt\n+++ b.txt\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"
+        );
+    }
+
+    #[test]
+    fn test_unified_matches_similar() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\nlast";
+        let new = "0\n1\n2\n3\n4\n5\n6\nseven\n8\n9\n10\nlast\n";
+        for context in 0..4 {
+            let expected = TextDiff::from_lines(old, new)
+                .unified_diff()
+                .context_radius(context)
+                .header("a", "b")
+                .to_string();
+            let patch =
+                TextDifference::unified(old, new, &DiffOptions::default(), context, "a", "b");
+            assert_eq!(patch, expected);
+        }
+        assert_eq!(
+            TextDifference::unified("x\n", "x\n", &DiffOptions::default(), 3, "a", "b"),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_options() {
+        let old = "key = value\r\n\n  Name: A\nend";
+        let new = "key=value\n    name:   a  \nend\n";
+        let changed = |options: DiffOptions| {
+            TextDifference::lines(old, new, &options)
+                .iter()
+                .filter(|l| l.tag != ChangeTag::Equal)
+                .count()
+        };
+        assert_eq!(changed(DiffOptions::default()), 7);
+
+        let options = DiffOptions {
+            ignore_all_whitespace: true,
+            ignore_blank_lines: true,
+            ignore_case: true,
+            ignore_line_endings: true,
+            ..Default::default()
+        };
+        assert_eq!(changed(options), 0);
+        // without blank lines ignored the empty line is still a change
+        assert_eq!(
+            changed(DiffOptions {
+                ignore_blank_lines: false,
+                ..options
+            }),
+            1
+        );
+
+        let options = DiffOptions {
+            ignore_whitespace_change: true,
+            ..Default::default()
+        };
+        assert_eq!(options.normalize("  a \t b  \n"), " a b\n");
+
+        // original text is kept on both sides
+        let lines = TextDifference::lines(
+            "A\n",
+            "a\n",
+            &DiffOptions {
+                ignore_case: true,
+                ..Default::default()
+            },
+        );
+        let rows = TextDifference::side_by_side(&lines);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].old.as_ref().unwrap().text(), "A\n");
+        assert_eq!(rows[0].new.as_ref().unwrap().text(), "a\n");
+    }
+
+    #[test]
+    fn test_stats() {
+        let old = "a\nb\nc\nd\ne\n";
+        let new = "a\nB\nc\nd\nx\ny\n";
+        let lines = TextDifference::lines(old, new, &DiffOptions::default());
+        assert_eq!(TextDifference::blocks(&lines), [1..3, 5..8]);
+
+        let stats = DiffStats::of(&lines);
+        assert_eq!(
+            (
+                stats.insertions,
+                stats.deletions,
+                stats.changed,
+                stats.blocks
+            ),
+            (3, 2, 2, 2)
+        );
+        let ratio = TextDifference::ratio(old, new, DiffGranularity::Line);
+        assert!((stats.similarity - ratio).abs() < f32::EPSILON);
+    }
 }
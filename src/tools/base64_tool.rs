@@ -1,12 +1,157 @@
-use super::{Tool, ToolLogic, ToolState, ToolUI};
-use base64::{engine::general_purpose::STANDARD, Engine as _};
+use std::{fs, slice::Iter};
+
+use super::{
+    from_json, to_json, ToolAction, ToolCategory, ToolError, ToolLogic, ToolResult, ToolState,
+    ToolUI,
+};
+use crate::model::{detect_magic, hex_dump};
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine as _,
+};
 use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+/// MIME (RFC 2045) limits encoded lines to 76 characters.
+const MIME_LINE_LEN: usize = 76;
+/// Bytes shown in the hex preview of binary output.
+const HEX_PREVIEW_LEN: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Base64Variant {
+    #[default]
+    Standard,
+    StandardNoPad,
+    UrlSafe,
+    UrlSafeNoPad,
+    Mime,
+}
 
-#[derive(Default)]
+impl Base64Variant {
+    pub fn iter() -> Iter<'static, Base64Variant> {
+        static VARIANTS: [Base64Variant; 5] = [
+            Base64Variant::Standard,
+            Base64Variant::StandardNoPad,
+            Base64Variant::UrlSafe,
+            Base64Variant::UrlSafeNoPad,
+            Base64Variant::Mime,
+        ];
+        VARIANTS.iter()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Base64Variant::Standard => "Standard",
+            Base64Variant::StandardNoPad => "Standard, no padding",
+            Base64Variant::UrlSafe => "URL-safe",
+            Base64Variant::UrlSafeNoPad => "URL-safe, no padding",
+            Base64Variant::Mime => "MIME",
+        }
+    }
+
+    fn engine(&self, padding: bool) -> GeneralPurpose {
+        let alphabet = match self {
+            Base64Variant::UrlSafe | Base64Variant::UrlSafeNoPad => &alphabet::URL_SAFE,
+            _ => &alphabet::STANDARD,
+        };
+        let config = GeneralPurposeConfig::new()
+            .with_encode_padding(padding)
+            .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+            .with_decode_allow_trailing_bits(true);
+        GeneralPurpose::new(alphabet, config)
+    }
+
+    fn padded(&self) -> bool {
+        !matches!(
+            self,
+            Base64Variant::StandardNoPad | Base64Variant::UrlSafeNoPad
+        )
+    }
+
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        let encoded = self.engine(self.padded()).encode(bytes);
+        if *self != Base64Variant::Mime {
+            return encoded;
+        }
+        encoded
+            .as_bytes()
+            .chunks(MIME_LINE_LEN)
+            .map(|line| String::from_utf8_lossy(line))
+            .collect::<Vec<_>>()
+            .join("\r\n")
+    }
+
+    /// Decodes leniently: whitespace and line breaks are skipped and padding
+    /// is optional.
+    pub fn decode(&self, input: &str) -> Result<Vec<u8>, String> {
+        let compact: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+        self.engine(self.padded())
+            .decode(compact)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Guesses the variant from the alphabet, padding and line wrapping used.
+    /// Returns `None` when the input is not valid base64 of any variant.
+    pub fn detect(input: &str) -> Option<Base64Variant> {
+        let compact: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+        if compact.is_empty() {
+            return None;
+        }
+        let url_safe = compact.contains(['-', '_']);
+        if url_safe && compact.contains(['+', '/']) {
+            return None;
+        }
+        let padded = compact.ends_with('=') || compact.len().is_multiple_of(4);
+        let wrapped = input.trim().contains('\n');
+
+        let variant = match (url_safe, wrapped, padded) {
+            (true, _, true) => Base64Variant::UrlSafe,
+            (true, _, false) => Base64Variant::UrlSafeNoPad,
+            (false, true, _) => Base64Variant::Mime,
+            (false, false, true) => Base64Variant::Standard,
+            (false, false, false) => Base64Variant::StandardNoPad,
+        };
+        variant.decode(input).ok().map(|_| variant)
+    }
+}
+
+/// Decoding is lenient, so plain words like "password" pass as base64 too.
+/// Clear base64 is padded to whole blocks and decodes to text or a known
+/// file type.
+fn is_strict_base64(input: &str) -> bool {
+    let compact: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    if !compact.len().is_multiple_of(4) {
+        return false;
+    }
+    Base64Variant::detect(input)
+        .and_then(|variant| variant.decode(input).ok())
+        .is_some_and(|bytes| std::str::from_utf8(&bytes).is_ok() || detect_magic(&bytes).is_some())
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Base64ToolState {
     input: String,
+    #[serde(skip)]
     output: String,
     is_encode: bool,
+    variant: Base64Variant,
+    /// Decode with the variant [`Base64Variant::detect`] finds.
+    auto_detect: bool,
+    #[serde(skip)]
+    detected: Option<Base64Variant>,
+    /// File name and contents to encode instead of `input`.
+    #[serde(skip)]
+    file: Option<(String, Vec<u8>)>,
+    /// Raw result of decoding, `output` only previews it.
+    #[serde(skip)]
+    decoded: Vec<u8>,
+    #[serde(skip)]
+    binary: bool,
+    #[serde(skip)]
+    file_type: Option<&'static str>,
+    #[serde(skip)]
     is_dirty: bool,
 }
 
@@ -14,6 +159,10 @@ impl ToolState for Base64ToolState {
     fn clear(&mut self) {
         self.input.clear();
         self.output.clear();
+        self.file = None;
+        self.decoded.clear();
+        self.binary = false;
+        self.file_type = None;
         self.is_dirty = false;
     }
 
@@ -26,6 +175,8 @@ impl ToolState for Base64ToolState {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Base64Tool {
     state: Base64ToolState,
 }
@@ -33,31 +184,134 @@ pub struct Base64Tool {
 impl Default for Base64Tool {
     fn default() -> Self {
         Self {
-            state: Base64ToolState::default(),
+            state: Base64ToolState {
+                is_encode: true,
+                auto_detect: true,
+                ..Default::default()
+            },
         }
     }
 }
 
 impl ToolLogic for Base64Tool {
-    fn process(&mut self) -> Result<(), String> {
-        if self.state.input.is_empty() {
-            return Err("输入不能为空".to_string());
+    fn process(&mut self) -> ToolResult<()> {
+        if !self.validate() {
+            return Err(ToolError::ValidationError("输入不能为空".to_string()));
         }
 
-        self.state.output = if self.state.is_encode {
-            STANDARD.encode(self.state.input.as_bytes())
-        } else {
-            match STANDARD.decode(self.state.input.as_bytes()) {
-                Ok(decoded) => String::from_utf8_lossy(&decoded).to_string(),
-                Err(e) => return Err(format!("解码失败: {}", e)),
+        let state = &mut self.state;
+        state.decoded.clear();
+        state.binary = false;
+        if state.is_encode {
+            let bytes = match &state.file {
+                Some((_, bytes)) => bytes.as_slice(),
+                None => state.input.as_bytes(),
+            };
+            state.file_type = detect_magic(bytes);
+            state.output = state.variant.encode(bytes);
+            return Ok(());
+        }
+
+        let variant = match state.detected {
+            Some(variant) if state.auto_detect => variant,
+            _ => state.variant,
+        };
+        let decoded = variant
+            .decode(&state.input)
+            .map_err(|e| ToolError::ProcessError(format!("解码失败: {}", e)))?;
+        state.file_type = detect_magic(&decoded);
+        state.output = match std::str::from_utf8(&decoded) {
+            Ok(text) => text.to_string(),
+            Err(_) => {
+                state.binary = true;
+                hex_dump(&decoded, HEX_PREVIEW_LEN)
             }
         };
-
+        state.decoded = decoded;
         Ok(())
     }
 
     fn validate(&self) -> bool {
-        !self.state.input.is_empty()
+        !self.state.input.is_empty() || (self.state.is_encode && self.state.file.is_some())
+    }
+
+    fn actions(&self) -> Vec<ToolAction> {
+        vec![
+            ToolAction {
+                id: "encode",
+                name: "Encode",
+            },
+            ToolAction {
+                id: "decode",
+                name: "Decode",
+            },
+        ]
+    }
+
+    fn run_action(&mut self, id: &str) -> ToolResult<()> {
+        self.state.is_encode = match id {
+            "encode" => true,
+            "decode" => false,
+            _ => return Err(ToolError::ProcessError(format!("unknown action {}", id))),
+        };
+        self.process()
+    }
+
+    fn output(&self) -> Option<String> {
+        Some(self.state.output.clone()).filter(|output| !output.is_empty())
+    }
+
+    fn output_is_binary(&self) -> bool {
+        self.state.binary
+    }
+
+    /// Switches to decoding for input that is clearly base64, otherwise the
+    /// direction is kept.
+    fn set_input(&mut self, input: &str) {
+        self.state.file = None;
+        self.state.input = input.to_string();
+        self.state.detected = Base64Variant::detect(input);
+        if is_strict_base64(input) {
+            self.state.is_encode = false;
+        }
+        self.state.is_dirty = true;
+    }
+}
+
+impl Base64Tool {
+    fn open_file(&mut self) {
+        let Some(path) = rfd::FileDialog::new().pick_file() else {
+            return;
+        };
+        match fs::read(&path) {
+            Ok(bytes) => {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                self.state.file = Some((name, bytes));
+                self.state.is_dirty = true;
+                if let Err(e) = self.process() {
+                    self.state.output = format!("error: {}", e);
+                }
+            }
+            Err(e) => self.state.output = format!("error: {}", e),
+        }
+    }
+
+    /// Saves the decoded bytes, or the encoded text when encoding.
+    fn save_output(&mut self) {
+        let Some(path) = rfd::FileDialog::new().save_file() else {
+            return;
+        };
+        let bytes = if self.state.is_encode {
+            self.state.output.as_bytes()
+        } else {
+            &self.state.decoded
+        };
+        if let Err(e) = fs::write(path, bytes) {
+            self.state.output = format!("error: {}", e);
+        }
     }
 }
 
@@ -71,12 +325,59 @@ impl ToolUI for Base64Tool {
             ui.horizontal(|ui| {
                 ui.radio_value(&mut self.state.is_encode, true, "Encode");
                 ui.radio_value(&mut self.state.is_encode, false, "Decode");
+                ui.separator();
+                let auto = !self.state.is_encode && self.state.auto_detect;
+                egui::ComboBox::from_id_salt("base64 variant")
+                    .selected_text(if auto {
+                        "Auto"
+                    } else {
+                        self.state.variant.name()
+                    })
+                    .show_ui(ui, |ui| {
+                        if !self.state.is_encode {
+                            ui.selectable_value(&mut self.state.auto_detect, true, "Auto");
+                        }
+                        Base64Variant::iter().for_each(|variant| {
+                            if ui
+                                .selectable_label(
+                                    !auto && self.state.variant == *variant,
+                                    variant.name(),
+                                )
+                                .clicked()
+                            {
+                                self.state.variant = *variant;
+                                self.state.auto_detect = false;
+                            }
+                        });
+                    });
             });
 
             // ui.label(if self.state.is_encode { "要编码的文本:" } else { "要解码的文本:" });
-            ui.label("input your text here:");
-            if ui.text_edit_multiline(&mut self.state.input).changed() {
-                self.state.is_dirty = true;
+            match &self.state.file {
+                Some((name, bytes)) if self.state.is_encode => {
+                    let mut closed = false;
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} ({} bytes)", name, bytes.len()));
+                        closed = ui.small_button("✖").clicked();
+                    });
+                    if closed {
+                        self.state.file = None;
+                    }
+                }
+                _ => {
+                    ui.label("input your text here:");
+                    if ui.text_edit_multiline(&mut self.state.input).changed() {
+                        self.state.is_dirty = true;
+                        self.state.detected = Base64Variant::detect(&self.state.input);
+                    }
+                }
+            }
+            if !self.state.is_encode && !self.state.input.trim().is_empty() {
+                let detected = self
+                    .state
+                    .detected
+                    .map_or("not valid base64", |variant| variant.name());
+                ui.small(format!("Detected: {}", detected));
             }
 
             ui.horizontal(|ui| {
@@ -85,6 +386,9 @@ impl ToolUI for Base64Tool {
                         self.state.output = format!("error: {}", e);
                     }
                 }
+                if self.state.is_encode && ui.button("Open file…").clicked() {
+                    self.open_file();
+                }
                 if ui.button("Clear").clicked() {
                     self.state.clear();
                 }
@@ -99,9 +403,20 @@ impl ToolUI for Base64Tool {
                         .desired_width(ui.available_width())
                         .font(egui::TextStyle::Monospace),
                 );
-                if ui.button("📋").on_hover_text("复制到剪贴板").clicked() {
-                    ui.output_mut(|o| o.copied_text = self.state.output.clone());
+                if let Some(file_type) = self.state.file_type {
+                    ui.small(format!("Content: {}", file_type));
                 }
+                if self.state.binary {
+                    ui.small("Not valid UTF-8, showing a hex dump");
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("📋").on_hover_text("复制到剪贴板").clicked() {
+                        ui.output_mut(|o| o.copied_text = self.state.output.clone());
+                    }
+                    if ui.button("Save…").clicked() {
+                        self.save_output();
+                    }
+                });
             }
         });
     }
@@ -113,31 +428,17 @@ impl ToolUI for Base64Tool {
     fn description(&self) -> &str {
         "Base64 Encoder/Decoder"
     }
-}
-
-impl Tool for Base64Tool {
-    fn as_state(&self) -> &dyn ToolState {
-        &self.state
-    }
 
-    fn as_state_mut(&mut self) -> &mut dyn ToolState {
-        &mut self.state
+    fn icon(&self) -> &str {
+        "🔤"
     }
 
-    fn as_logic(&self) -> &dyn ToolLogic {
-        self
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Encoding
     }
 
-    fn as_logic_mut(&mut self) -> &mut dyn ToolLogic {
-        self
-    }
-
-    fn as_ui(&self) -> &dyn ToolUI {
-        self
-    }
-
-    fn as_ui_mut(&mut self) -> &mut dyn ToolUI {
-        self
+    fn process_label(&self) -> &str {
+        "Encode/Decode"
     }
 }
 
@@ -153,4 +454,91 @@ impl ToolState for Base64Tool {
     fn mark_clean(&mut self) {
         self.state.mark_clean();
     }
+
+    fn save_state(&self) -> Option<String> {
+        to_json(self)
+    }
+
+    fn load_state(&mut self, state: &str) -> ToolResult<()> {
+        *self = from_json(state)?;
+        self.state.detected = Base64Variant::detect(&self.state.input);
+        if self.validate() {
+            if let Err(e) = self.process() {
+                self.state.output = format!("error: {}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_variants() {
+        let bytes = [0xfbu8, 0xff, 0x01];
+        assert_eq!(Base64Variant::Standard.encode(&bytes), "+/8B");
+        assert_eq!(Base64Variant::UrlSafe.encode(&bytes), "-_8B");
+        assert_eq!(Base64Variant::StandardNoPad.encode(b"ab"), "YWI");
+        assert_eq!(Base64Variant::Standard.encode(b"ab"), "YWI=");
+
+        let mime = Base64Variant::Mime.encode(&[0u8; 60]);
+        assert_eq!(
+            mime.split("\r\n").map(str::len).collect::<Vec<_>>(),
+            [76, 4]
+        );
+        assert_eq!(Base64Variant::Mime.decode(&mime).unwrap(), [0u8; 60]);
+
+        // lenient: whitespace and missing padding are accepted
+        assert_eq!(Base64Variant::Standard.decode(" YW\nI ").unwrap(), b"ab");
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Base64Variant::detect("YWI="), Some(Base64Variant::Standard));
+        assert_eq!(
+            Base64Variant::detect("YWI"),
+            Some(Base64Variant::StandardNoPad)
+        );
+        assert_eq!(Base64Variant::detect("-_8B"), Some(Base64Variant::UrlSafe));
+        assert_eq!(
+            Base64Variant::detect("-_8"),
+            Some(Base64Variant::UrlSafeNoPad)
+        );
+        let mime = Base64Variant::Mime.encode(&[1u8; 90]);
+        assert_eq!(Base64Variant::detect(&mime), Some(Base64Variant::Mime));
+        assert_eq!(Base64Variant::detect("+_8B"), None);
+        assert_eq!(Base64Variant::detect("not base64!"), None);
+    }
+
+    #[test]
+    fn test_set_input() {
+        let mut tool = Base64Tool::default();
+        tool.set_input("password");
+        assert!(tool.state.is_encode);
+        tool.set_input("cGFzc3dvcmQ=");
+        assert!(!tool.state.is_encode);
+        // unclear input keeps the direction
+        tool.set_input("admin123");
+        assert!(!tool.state.is_encode);
+
+        let png = Base64Variant::Standard.encode(b"\x89PNG\r\n\x1a\n\xff");
+        let mut tool = Base64Tool::default();
+        tool.set_input(&png);
+        assert!(!tool.state.is_encode);
+    }
+
+    #[test]
+    fn test_binary_decode() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        let mut tool = Base64Tool::default();
+        tool.state.is_encode = false;
+        tool.state.input = Base64Variant::Standard.encode(png);
+        tool.process().unwrap();
+        assert_eq!(tool.state.decoded, png);
+        assert!(tool.state.binary);
+        assert_eq!(tool.state.file_type, Some("PNG image"));
+        assert!(tool.state.output.starts_with("00000000  89 50 4e 47"));
+    }
 }
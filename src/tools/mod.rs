@@ -1,4 +1,7 @@
 pub mod base64_tool;
+pub mod pipeline;
+pub mod registry;
+pub mod workspace;
 
 // pub mod calculator_tool;
 // pub mod datetime_tool;
@@ -8,19 +11,74 @@ pub mod base64_tool;
 // pub mod json_tool;
 // pub mod sql_tool;
 
+pub use pipeline::{Pipeline, PipelineStep, StepResult};
+pub use registry::{ToolCategory, ToolEntry, ToolRegistry};
+pub use workspace::{SavedTab, SavedWorkspace, Tab, Workspace};
+
+use std::fmt;
+
 use eframe::egui;
+use serde::{de::DeserializeOwned, Serialize};
 
 // 定义工具的状态管理特征
 pub trait ToolState {
     fn clear(&mut self);
-    fn is_dirty(&self) -> bool;
-    fn mark_clean(&mut self);
+
+    /// Tools that don't track unsaved input are never dirty.
+    fn is_dirty(&self) -> bool {
+        false
+    }
+
+    fn mark_clean(&mut self) {}
+
+    /// State worth keeping across restarts, `None` when nothing is kept.
+    fn save_state(&self) -> Option<String> {
+        None
+    }
+
+    /// Restores what [`ToolState::save_state`] returned. On error the
+    /// current state is left untouched.
+    fn load_state(&mut self, _state: &str) -> ToolResult<()> {
+        Ok(())
+    }
 }
 
 // 定义工具的业务逻辑特征
 pub trait ToolLogic {
-    fn process(&mut self) -> Result<(), String>;
+    fn process(&mut self) -> ToolResult<()>;
     fn validate(&self) -> bool;
+
+    /// Commands besides `process` and `clear`, e.g. minifying JSON.
+    fn actions(&self) -> Vec<ToolAction> {
+        Vec::new()
+    }
+
+    fn run_action(&mut self, id: &str) -> ToolResult<()> {
+        Err(ToolError::ProcessError(format!("unknown action {}", id)))
+    }
+
+    /// The text "Copy result" puts on the clipboard.
+    fn output(&self) -> Option<String> {
+        None
+    }
+
+    /// Whether the result is bytes that `output` only previews, e.g. as a
+    /// hex dump.
+    fn output_is_binary(&self) -> bool {
+        false
+    }
+
+    /// Replaces the main input, e.g. with pasted text. Tools without a text
+    /// input ignore it.
+    fn set_input(&mut self, _input: &str) {}
+}
+
+/// A tool specific command for the command palette and keymap, where it is
+/// bound as `<tool id>.<action id>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolAction {
+    pub id: &'static str,
+    pub name: &'static str,
 }
 
 // 定义工具的UI渲染特征
@@ -28,6 +86,14 @@ pub trait ToolUI {
     fn render(&mut self, ui: &mut egui::Ui);
     fn name(&self) -> &str;
     fn description(&self) -> &str;
+    /// A single glyph shown next to the name in the sidebar.
+    fn icon(&self) -> &str;
+    fn category(&self) -> ToolCategory;
+
+    /// What `process` is called in the command palette.
+    fn process_label(&self) -> &str {
+        "Run"
+    }
 }
 
 // 定义完整的工具特征，组合以上特征
@@ -40,12 +106,67 @@ pub trait Tool: ToolState + ToolLogic + ToolUI {
     fn as_ui_mut(&mut self) -> &mut dyn ToolUI;
 }
 
+impl<T: ToolState + ToolLogic + ToolUI> Tool for T {
+    fn as_state(&self) -> &dyn ToolState {
+        self
+    }
+
+    fn as_state_mut(&mut self) -> &mut dyn ToolState {
+        self
+    }
+
+    fn as_logic(&self) -> &dyn ToolLogic {
+        self
+    }
+
+    fn as_logic_mut(&mut self) -> &mut dyn ToolLogic {
+        self
+    }
+
+    fn as_ui(&self) -> &dyn ToolUI {
+        self
+    }
+
+    fn as_ui_mut(&mut self) -> &mut dyn ToolUI {
+        self
+    }
+}
+
 // 错误处理
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ToolError {
     ValidationError(String),
     ProcessError(String),
     StateError(String),
 }
 
+impl fmt::Display for ToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolError::ValidationError(msg)
+            | ToolError::ProcessError(msg)
+            | ToolError::StateError(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for ToolError {}
+
 pub type ToolResult<T> = Result<T, ToolError>;
+
+/// Turns the `err_msg` a view shows into the result of `process`.
+pub(crate) fn process_result(err_msg: &str) -> ToolResult<()> {
+    if err_msg.is_empty() {
+        Ok(())
+    } else {
+        Err(ToolError::ProcessError(err_msg.to_string()))
+    }
+}
+
+pub(crate) fn to_json<T: Serialize>(state: &T) -> Option<String> {
+    serde_json::to_string(state).ok()
+}
+
+pub(crate) fn from_json<T: DeserializeOwned>(state: &str) -> ToolResult<T> {
+    serde_json::from_str(state).map_err(|e| ToolError::StateError(e.to_string()))
+}
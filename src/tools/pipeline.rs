@@ -0,0 +1,206 @@
+// chains tools, the output of each step is the input of the next one
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{ToolError, ToolRegistry, ToolResult};
+use crate::config::{config_file, load_or_default, save_file};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipelineStep {
+    /// Registry id of the tool.
+    pub tool: String,
+    /// Runs this `ToolLogic::run_action` instead of `process`.
+    pub action: Option<String>,
+    /// A `ToolState::save_state` string that sets the tool's options before
+    /// the input is given to it.
+    pub state: Option<String>,
+}
+
+impl PipelineStep {
+    pub fn new(tool: &str, action: Option<&str>) -> Self {
+        Self {
+            tool: tool.to_string(),
+            action: action.map(str::to_string),
+            state: None,
+        }
+    }
+
+    pub fn run(&self, registry: &ToolRegistry, input: &str) -> ToolResult<String> {
+        let mut tool = registry
+            .create(&self.tool)
+            .ok_or_else(|| ToolError::ValidationError(format!("unknown tool {}", self.tool)))?;
+        if let Some(state) = &self.state {
+            tool.load_state(state)?;
+        }
+        tool.set_input(input);
+        match &self.action {
+            Some(action) => tool.run_action(action)?,
+            None if tool.validate() => tool.process()?,
+            None => {
+                return Err(ToolError::ValidationError(format!(
+                    "{} cannot process this input",
+                    tool.name()
+                )))
+            }
+        }
+        // the next step would get the preview instead of the bytes
+        if tool.output_is_binary() {
+            return Err(ToolError::ProcessError(format!(
+                "{} output is binary",
+                tool.name()
+            )));
+        }
+        tool.output()
+            .ok_or_else(|| ToolError::ProcessError(format!("{} gave no output", tool.name())))
+    }
+}
+
+/// Input and result of one step of a run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepResult {
+    pub input: String,
+    pub output: ToolResult<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Pipeline {
+    pub name: String,
+    pub steps: Vec<PipelineStep>,
+}
+
+impl Pipeline {
+    /// Runs every step and keeps their results, up to the first failing one.
+    pub fn run(&self, registry: &ToolRegistry, input: &str) -> Vec<StepResult> {
+        let mut results: Vec<StepResult> = Vec::new();
+        let mut input = input.to_string();
+        for step in &self.steps {
+            let output = step.run(registry, &input);
+            let next = output.as_ref().ok().cloned();
+            results.push(StepResult { input, output });
+            match next {
+                Some(next) => input = next,
+                None => break,
+            }
+        }
+        results
+    }
+
+    /// Output of the last step, for running a pipeline without the UI.
+    pub fn apply(&self, registry: &ToolRegistry, input: &str) -> ToolResult<String> {
+        if self.steps.is_empty() {
+            return Err(ToolError::ValidationError(format!(
+                "pipeline {} has no steps",
+                self.name
+            )));
+        }
+        self.run(registry, input)
+            .pop()
+            .map(|result| result.output)
+            .unwrap_or_else(|| Ok(input.to_string()))
+    }
+
+    /// `pipelines.json` next to the app's saved state.
+    pub fn file() -> Option<PathBuf> {
+        config_file("pipelines.json")
+    }
+
+    /// Reads saved pipelines, a missing file gives none.
+    pub fn load_all(path: &Path) -> Result<Vec<Pipeline>, String> {
+        load_or_default(path, |src| {
+            serde_json::from_str(src).map_err(|e| e.to_string())
+        })
+    }
+
+    pub fn save_all(path: &Path, pipelines: &[Pipeline]) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(pipelines).map_err(|e| e.to_string())?;
+        save_file(path, &json)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pipeline() {
+        let registry = ToolRegistry::builtin();
+        let pipeline = Pipeline {
+            name: "decode and hash".to_string(),
+            steps: vec![
+                PipelineStep::new("base64", Some("decode")),
+                PipelineStep::new("json", None),
+                PipelineStep::new("hash", Some("md5")),
+            ],
+        };
+        // base64 of {"a":[1,2]}
+        let results = pipeline.run(&registry, "eyJhIjpbMSwyXX0=");
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1].input, r#"{"a":[1,2]}"#);
+        let pretty = results[1].output.clone().unwrap();
+        assert!(pretty.contains('\n'));
+        assert_eq!(results[2].input, pretty);
+        assert_eq!(
+            pipeline.apply(&registry, "eyJhIjpbMSwyXX0="),
+            Ok(crate::model::HashAlgorithm::MD5.hash(&pretty))
+        );
+
+        // a failing step ends the run
+        let results = pipeline.run(&registry, "bm90IGpzb24=");
+        assert_eq!(results.len(), 2);
+        assert!(results[1].output.is_err());
+
+        // binary output isn't passed on as its hex dump
+        let results = pipeline.run(&registry, "AAEC/w==");
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].output,
+            Err(ToolError::ProcessError(
+                "Base64 output is binary".to_string()
+            ))
+        );
+
+        // plain text that happens to use the base64 alphabet is encoded
+        let encode = Pipeline {
+            steps: vec![PipelineStep::new("base64", None)],
+            ..Default::default()
+        };
+        assert_eq!(
+            encode.apply(&registry, "password"),
+            Ok("cGFzc3dvcmQ=".to_string())
+        );
+
+        let unknown = Pipeline {
+            steps: vec![PipelineStep::new("nope", None)],
+            ..Default::default()
+        };
+        assert!(unknown.apply(&registry, "x").is_err());
+        assert!(Pipeline::default().apply(&registry, "x").is_err());
+    }
+
+    #[test]
+    fn test_step_state() {
+        let registry = ToolRegistry::builtin();
+        let mut step = PipelineStep::new("number-base", None);
+        step.state = Some(r#"{"not": "a number view"}"#.to_string());
+        assert!(step.run(&registry, "255").is_ok());
+        step.state = Some("not json".to_string());
+        assert!(matches!(
+            step.run(&registry, "255"),
+            Err(ToolError::StateError(_))
+        ));
+
+        let pipelines = vec![Pipeline {
+            name: "hash".to_string(),
+            steps: vec![step],
+        }];
+        let json = serde_json::to_string(&pipelines).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<Pipeline>>(&json).unwrap(),
+            pipelines
+        );
+    }
+}
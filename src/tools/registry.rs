@@ -0,0 +1,188 @@
+// the list of tools the app offers
+
+use std::slice::Iter;
+
+use super::{base64_tool::Base64Tool, Tool};
+use crate::view::{
+    calculator_view::CalcView, cron_view::CronView, datetime_view::DateTimeConverterView,
+    differ_view::DifferenceView, formatter_view::FormatterView, game_of_life::GameOfLifeView,
+    hash_view::HashView, jwt_view::JwtView, number_view::NumberBaseConverterView,
+    patch_view::PatchView, pipeline_view::PipelineView,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToolCategory {
+    Encoding,
+    Formatting,
+    Conversion,
+    Text,
+    Math,
+    Automation,
+    Fun,
+}
+
+impl ToolCategory {
+    pub fn iter() -> Iter<'static, ToolCategory> {
+        static CATEGORIES: [ToolCategory; 7] = [
+            ToolCategory::Encoding,
+            ToolCategory::Formatting,
+            ToolCategory::Conversion,
+            ToolCategory::Text,
+            ToolCategory::Math,
+            ToolCategory::Automation,
+            ToolCategory::Fun,
+        ];
+        CATEGORIES.iter()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ToolCategory::Encoding => "Encoding & Hashing",
+            ToolCategory::Formatting => "Formatters",
+            ToolCategory::Conversion => "Converters",
+            ToolCategory::Text => "Text",
+            ToolCategory::Math => "Math",
+            ToolCategory::Automation => "Automation",
+            ToolCategory::Fun => "Fun",
+        }
+    }
+}
+
+/// A registered tool. The metadata is read once from a fresh instance so
+/// every tool declares it in its own `ToolUI` impl.
+pub struct ToolEntry {
+    /// Stable identifier, never shown to the user.
+    pub id: &'static str,
+    pub name: String,
+    pub description: String,
+    pub icon: String,
+    pub category: ToolCategory,
+    factory: fn() -> Box<dyn Tool>,
+}
+
+impl ToolEntry {
+    pub fn create(&self) -> Box<dyn Tool> {
+        (self.factory)()
+    }
+}
+
+#[derive(Default)]
+pub struct ToolRegistry {
+    entries: Vec<ToolEntry>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every tool shipped with the app, in sidebar order.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register("hash", || Box::new(HashView::new()));
+        registry.register("base64", || Box::new(Base64Tool::default()));
+        registry.register("jwt", || Box::new(JwtView::new()));
+        registry.register("json", || Box::new(FormatterView::new("json")));
+        registry.register("sql", || Box::new(FormatterView::new("sql")));
+        registry.register("datetime", || Box::new(DateTimeConverterView::new()));
+        registry.register("cron", || Box::new(CronView::new()));
+        registry.register("number-base", || Box::new(NumberBaseConverterView::new()));
+        registry.register("diff", || Box::new(DifferenceView::new()));
+        registry.register("patch", || Box::new(PatchView::new()));
+        registry.register("calculator", || Box::new(CalcView::new()));
+        registry.register("pipeline", || Box::new(PipelineView::new()));
+        registry.register("game-of-life", || Box::new(GameOfLifeView::default()));
+        registry
+    }
+
+    /// Adds a tool. Panics when `id` is already taken.
+    pub fn register(&mut self, id: &'static str, factory: fn() -> Box<dyn Tool>) {
+        assert!(self.get(id).is_none(), "tool `{}` registered twice", id);
+        let tool = factory();
+        self.entries.push(ToolEntry {
+            id,
+            name: tool.name().to_string(),
+            description: tool.description().to_string(),
+            icon: tool.icon().to_string(),
+            category: tool.category(),
+            factory,
+        });
+    }
+
+    pub fn iter(&self) -> Iter<'_, ToolEntry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ToolEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    pub fn create(&self, id: &str) -> Option<Box<dyn Tool>> {
+        self.get(id).map(ToolEntry::create)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tools::ToolError;
+
+    #[test]
+    fn test_builtin_registry() {
+        let registry = ToolRegistry::builtin();
+        assert_eq!(registry.len(), 13);
+
+        let json = registry.get("json").unwrap();
+        assert_eq!(json.name, "Json Formatter");
+        assert_eq!(json.category, ToolCategory::Formatting);
+        assert!(!json.icon.is_empty() && !json.description.is_empty());
+
+        let mut tool = registry.create("number-base").unwrap();
+        assert_eq!(tool.category(), ToolCategory::Conversion);
+        assert!(tool.as_logic_mut().process().is_ok());
+        assert!(registry.create("missing").is_none());
+
+        // every category shows up in the sidebar
+        for category in ToolCategory::iter() {
+            assert!(registry.iter().any(|entry| entry.category == *category));
+        }
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let registry = ToolRegistry::builtin();
+        for entry in registry.iter() {
+            let saved = entry.create().save_state().unwrap();
+            let mut tool = entry.create();
+            tool.load_state(&saved).unwrap();
+            assert_eq!(tool.save_state().unwrap(), saved, "{}", entry.id);
+        }
+
+        let mut cron = registry.create("cron").unwrap();
+        cron.load_state(r#"{"input":"0 12 * * *","zone":"Asia/Tokyo"}"#)
+            .unwrap();
+        let saved = cron.save_state().unwrap();
+        assert!(saved.contains(r#""zone":"Asia/Tokyo""#));
+        assert!(matches!(
+            cron.load_state(r#"{"zone":"Nowhere/Atlantis"}"#),
+            Err(ToolError::StateError(_))
+        ));
+        assert_eq!(cron.save_state().unwrap(), saved);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_duplicate_id() {
+        let mut registry = ToolRegistry::new();
+        registry.register("hash", || Box::new(HashView::new()));
+        registry.register("hash", || Box::new(HashView::new()));
+    }
+}
@@ -0,0 +1,297 @@
+// open tabs, each one an independent instance of a registry tool
+
+use serde::{Deserialize, Serialize};
+
+use super::{Tool, ToolEntry, ToolRegistry};
+
+pub struct Tab {
+    /// Unique for the lifetime of the workspace, used as the egui id.
+    pub id: u64,
+    pub tool_id: String,
+    pub title: String,
+    pub tool: Box<dyn Tool>,
+}
+
+/// What [`Workspace::save`] keeps of a tab.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedTab {
+    pub tool: String,
+    pub title: String,
+    /// Result of `ToolState::save_state`.
+    pub state: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedWorkspace {
+    pub tabs: Vec<SavedTab>,
+    pub active: Option<usize>,
+}
+
+#[derive(Default)]
+pub struct Workspace {
+    tabs: Vec<Tab>,
+    active: Option<usize>,
+    next_id: u64,
+}
+
+impl Workspace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn tabs(&self) -> &[Tab] {
+        &self.tabs
+    }
+
+    pub fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+
+    pub fn active(&self) -> Option<usize> {
+        self.active
+    }
+
+    pub fn active_tab(&self) -> Option<&Tab> {
+        self.tabs.get(self.active?)
+    }
+
+    pub fn active_tab_mut(&mut self) -> Option<&mut Tab> {
+        self.tabs.get_mut(self.active?)
+    }
+
+    pub fn tab_mut(&mut self, index: usize) -> Option<&mut Tab> {
+        self.tabs.get_mut(index)
+    }
+
+    pub fn focus(&mut self, index: usize) {
+        if index < self.tabs.len() {
+            self.active = Some(index);
+        }
+    }
+
+    /// Opens `entry` in a new tab after the last one and focuses it. Tabs of
+    /// a tool that is already open are numbered, e.g. `Json Formatter 2`.
+    pub fn open(&mut self, entry: &ToolEntry) -> usize {
+        let title = match self.tabs.iter().filter(|t| t.tool_id == entry.id).count() {
+            0 => entry.name.clone(),
+            count => format!("{} {}", entry.name, count + 1),
+        };
+        self.push(entry.id.to_string(), title, entry.create());
+        self.tabs.len() - 1
+    }
+
+    /// Focuses a tab of `entry`, the current one if it is already of that
+    /// tool, otherwise the first, and opens one if there is none.
+    pub fn focus_or_open(&mut self, entry: &ToolEntry) -> usize {
+        let index = match self.active_tab() {
+            Some(tab) if tab.tool_id == entry.id => self.active,
+            _ => self.tabs.iter().position(|tab| tab.tool_id == entry.id),
+        };
+        match index {
+            Some(index) => {
+                self.active = Some(index);
+                index
+            }
+            None => self.open(entry),
+        }
+    }
+
+    fn push(&mut self, tool_id: String, title: String, tool: Box<dyn Tool>) {
+        self.tabs.push(Tab {
+            id: self.next_id,
+            tool_id,
+            title,
+            tool,
+        });
+        self.next_id += 1;
+        self.active = Some(self.tabs.len() - 1);
+    }
+
+    /// Closing the current tab focuses the one that takes its place, or the
+    /// new last tab.
+    pub fn close(&mut self, index: usize) {
+        if index >= self.tabs.len() {
+            return;
+        }
+        self.tabs.remove(index);
+        self.active = match self.active {
+            _ if self.tabs.is_empty() => None,
+            Some(active) if active > index => Some(active - 1),
+            Some(active) => Some(active.min(self.tabs.len() - 1)),
+            None => None,
+        };
+    }
+
+    /// Moves the tab at `from` to position `to`, the focus stays on the
+    /// same tab.
+    pub fn move_tab(&mut self, from: usize, to: usize) {
+        if from >= self.tabs.len() || to >= self.tabs.len() || from == to {
+            return;
+        }
+        let active_id = self.active_tab().map(|tab| tab.id);
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+        self.active = active_id.and_then(|id| self.tabs.iter().position(|tab| tab.id == id));
+    }
+
+    /// Blank titles are ignored.
+    pub fn rename(&mut self, index: usize, title: &str) {
+        let title = title.trim();
+        if let (Some(tab), false) = (self.tabs.get_mut(index), title.is_empty()) {
+            tab.title = title.to_string();
+        }
+    }
+
+    /// Focuses the tab `step` places away, wrapping around.
+    pub fn cycle(&mut self, step: isize) {
+        if self.tabs.is_empty() {
+            return;
+        }
+        let len = self.tabs.len() as isize;
+        let next = match self.active {
+            Some(active) => (active as isize + step).rem_euclid(len),
+            None if step > 0 => 0,
+            None => len - 1,
+        };
+        self.active = Some(next as usize);
+    }
+
+    pub fn clear(&mut self) {
+        self.tabs.clear();
+        self.active = None;
+    }
+
+    pub fn save(&self) -> SavedWorkspace {
+        SavedWorkspace {
+            tabs: self
+                .tabs
+                .iter()
+                .map(|tab| SavedTab {
+                    tool: tab.tool_id.clone(),
+                    title: tab.title.clone(),
+                    state: tab.tool.save_state(),
+                })
+                .collect(),
+            active: self.active,
+        }
+    }
+
+    /// Tabs of tools that are no longer registered are dropped, as is state
+    /// from an incompatible version, which leaves the tool fresh.
+    pub fn restore(registry: &ToolRegistry, saved: SavedWorkspace) -> Self {
+        let mut workspace = Self::new();
+        let mut active = None;
+        for (index, saved_tab) in saved.tabs.into_iter().enumerate() {
+            let Some(entry) = registry.get(&saved_tab.tool) else {
+                continue;
+            };
+            let mut tool = entry.create();
+            if let Some(state) = &saved_tab.state {
+                let _ = tool.load_state(state);
+            }
+            if saved.active == Some(index) {
+                active = Some(workspace.tabs.len());
+            }
+            let title = match saved_tab.title.trim() {
+                "" => entry.name.clone(),
+                title => title.to_string(),
+            };
+            workspace.push(saved_tab.tool, title, tool);
+        }
+        workspace.active = active.or(workspace.tabs.len().checked_sub(1));
+        workspace
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn titles(workspace: &Workspace) -> Vec<&str> {
+        workspace
+            .tabs()
+            .iter()
+            .map(|tab| tab.title.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_tabs() {
+        let registry = ToolRegistry::builtin();
+        let (json, diff) = (registry.get("json").unwrap(), registry.get("diff").unwrap());
+        let mut workspace = Workspace::new();
+        assert_eq!(workspace.active(), None);
+
+        assert_eq!(workspace.open(json), 0);
+        assert_eq!(workspace.open(diff), 1);
+        assert_eq!(workspace.open(json), 2);
+        assert_eq!(
+            titles(&workspace),
+            ["Json Formatter", "Text Difference", "Json Formatter 2"]
+        );
+        assert_eq!(workspace.active(), Some(2));
+
+        // the current json tab is kept, otherwise the first one is focused
+        assert_eq!(workspace.focus_or_open(json), 2);
+        workspace.focus(1);
+        assert_eq!(workspace.focus_or_open(json), 0);
+
+        workspace.move_tab(0, 2);
+        assert_eq!(
+            titles(&workspace),
+            ["Text Difference", "Json Formatter 2", "Json Formatter"]
+        );
+        assert_eq!(workspace.active(), Some(2));
+
+        workspace.rename(1, "  payload ");
+        workspace.rename(0, " ");
+        assert_eq!(
+            titles(&workspace),
+            ["Text Difference", "payload", "Json Formatter"]
+        );
+
+        workspace.close(2);
+        assert_eq!(workspace.active(), Some(1));
+        workspace.cycle(1);
+        assert_eq!(workspace.active(), Some(0));
+        workspace.close(1);
+        workspace.close(0);
+        assert!(workspace.is_empty());
+        assert_eq!(workspace.active(), None);
+    }
+
+    #[test]
+    fn test_save_restore() {
+        let registry = ToolRegistry::builtin();
+        let mut workspace = Workspace::new();
+        for id in ["json", "json", "hash"] {
+            workspace.open(registry.get(id).unwrap());
+        }
+        workspace.tab_mut(1).unwrap().tool.set_input(r#"{"a":1}"#);
+        workspace.focus(1);
+
+        let mut saved = workspace.save();
+        let restored = Workspace::restore(&registry, saved.clone());
+        assert_eq!(restored.save(), saved);
+        assert_ne!(saved.tabs[0].state, saved.tabs[1].state);
+
+        // unknown tools are dropped and the focus follows its tab
+        saved.tabs.insert(
+            0,
+            SavedTab {
+                tool: "gone".to_string(),
+                ..Default::default()
+            },
+        );
+        saved.active = Some(2);
+        let restored = Workspace::restore(&registry, saved);
+        assert_eq!(restored.len(), 3);
+        assert_eq!(restored.active(), Some(1));
+    }
+}
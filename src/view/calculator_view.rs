@@ -1,15 +1,20 @@
 use egui::{Layout, RichText};
 use egui_extras::{Column, TableBuilder};
+use serde::{Deserialize, Serialize};
 
 use crate::model::Expr;
 
-use super::View;
+use crate::tools::{
+    from_json, process_result, to_json, ToolCategory, ToolLogic, ToolResult, ToolState, ToolUI,
+};
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CalcView {
     pub input: String,
     pub output: Vec<String>,
     pub result: Vec<(String, i64)>,
+    #[serde(skip)]
     pub err_msg: String,
     pub reversed: bool,
 }
@@ -108,8 +113,64 @@ impl CalcView {
     }
 }
 
-impl View for CalcView {
-    fn render(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+impl ToolState for CalcView {
+    fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    fn save_state(&self) -> Option<String> {
+        to_json(self)
+    }
+
+    fn load_state(&mut self, state: &str) -> ToolResult<()> {
+        *self = from_json(state)?;
+        Ok(())
+    }
+}
+
+impl ToolLogic for CalcView {
+    fn process(&mut self) -> ToolResult<()> {
+        self.err_msg.clear();
+        self.calculate();
+        process_result(&self.err_msg)
+    }
+
+    fn validate(&self) -> bool {
+        !self.input.trim().is_empty()
+    }
+
+    /// The latest result.
+    fn output(&self) -> Option<String> {
+        self.result.last().map(|(_, value)| value.to_string())
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.input = input.to_string();
+    }
+}
+
+impl ToolUI for CalcView {
+    fn name(&self) -> &str {
+        "Calculator"
+    }
+
+    fn description(&self) -> &str {
+        "Evaluate arithmetic expressions and keep a history of results."
+    }
+
+    fn icon(&self) -> &str {
+        "🖩"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Math
+    }
+
+    fn process_label(&self) -> &str {
+        "Evaluate"
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
             ui.heading("Calculator");
             ui.separator();
@@ -143,7 +204,7 @@ impl View for CalcView {
             }
 
             if !self.err_msg.is_empty() {
-                ui.colored_label(egui::Color32::RED, &self.err_msg);
+                ui.colored_label(ui.visuals().error_fg_color, &self.err_msg);
             }
 
             ui.add_space(8.0);
@@ -0,0 +1,108 @@
+use egui::{Key, Modifiers};
+
+use crate::model::fuzzy_filter;
+
+/// Rows shown at once, the best matches come first.
+const MAX_ROWS: usize = 12;
+
+/// An entry of the palette and the value it yields when chosen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteItem<T> {
+    pub label: String,
+    /// Shown on hover.
+    pub detail: String,
+    pub shortcut: Option<String>,
+    pub value: T,
+}
+
+/// Popup with a search box that fuzzy filters a list of items, driven by
+/// the keyboard: arrows move, Enter picks, Escape closes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandPalette {
+    pub open: bool,
+    query: String,
+    selected: usize,
+}
+
+impl CommandPalette {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.query.clear();
+        self.selected = 0;
+    }
+
+    /// Shows the palette when it is open and returns the picked item's value.
+    /// Call it before the panels so it gets the arrow and Enter keys first.
+    pub fn show<T>(&mut self, ctx: &egui::Context, items: Vec<PaletteItem<T>>) -> Option<T> {
+        if !self.open {
+            return None;
+        }
+
+        let mut items = fuzzy_filter(&self.query, items, |item| &item.label);
+        items.truncate(MAX_ROWS);
+        let (up, down, enter, escape) = ctx.input_mut(|input| {
+            (
+                input.consume_key(Modifiers::NONE, Key::ArrowUp),
+                input.consume_key(Modifiers::NONE, Key::ArrowDown),
+                input.consume_key(Modifiers::NONE, Key::Enter),
+                input.consume_key(Modifiers::NONE, Key::Escape),
+            )
+        });
+        if escape {
+            self.open = false;
+            return None;
+        }
+        if down {
+            self.selected = (self.selected + 1).min(items.len().saturating_sub(1));
+        }
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+
+        let mut chosen = enter.then_some(self.selected);
+        egui::Window::new("Command palette")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .fixed_size([420.0, 0.0])
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text("Search tools and commands…")
+                        .desired_width(f32::INFINITY),
+                );
+                response.request_focus();
+                if response.changed() {
+                    self.selected = 0;
+                }
+                ui.separator();
+
+                if items.is_empty() {
+                    ui.weak("No matches");
+                }
+                for (index, item) in items.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let label = ui
+                            .selectable_label(index == self.selected, &item.label)
+                            .on_hover_text(&item.detail);
+                        if let Some(shortcut) = &item.shortcut {
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    ui.weak(shortcut);
+                                },
+                            );
+                        }
+                        if label.clicked() {
+                            chosen = Some(index);
+                        }
+                    });
+                }
+            });
+
+        let item = items.into_iter().nth(chosen?)?;
+        self.open = false;
+        Some(item.value)
+    }
+}
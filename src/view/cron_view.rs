@@ -0,0 +1,234 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use egui::RichText;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{CronExpression, DateTimeConverter, Zone};
+
+use super::datetime_view::DateTimeConverterView;
+use crate::tools::{
+    from_json, process_result, to_json, ToolCategory, ToolLogic, ToolResult, ToolState, ToolUI,
+};
+
+/// Zone and run count the upcoming runs were found for.
+type UpcomingKey = (Zone, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CronView {
+    pub input: String,
+    pub zone: Zone,
+    pub count: usize,
+    #[serde(skip)]
+    pub err_msg: String,
+    #[serde(skip)]
+    expression: Option<CronExpression>,
+    #[serde(skip)]
+    zone_filter: String,
+    /// Next runs and the zone and count they were computed for, the
+    /// search can scan years so it does not run every frame.
+    #[serde(skip)]
+    upcoming: Option<(UpcomingKey, Vec<DateTime<FixedOffset>>)>,
+}
+
+impl Default for CronView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CronView {
+    pub fn new() -> Self {
+        let mut view = Self {
+            input: String::from("*/15 9-17 * * MON-FRI"),
+            zone: Zone::Local,
+            count: 10,
+            err_msg: String::new(),
+            expression: None,
+            zone_filter: String::new(),
+            upcoming: None,
+        };
+        view.parse();
+        view
+    }
+
+    fn parse(&mut self) {
+        self.err_msg.clear();
+        self.upcoming = None;
+        match CronExpression::parse(&self.input) {
+            Ok(expression) => self.expression = Some(expression),
+            Err(e) => {
+                self.expression = None;
+                self.err_msg = e;
+            }
+        }
+    }
+
+    fn field_list(ui: &mut egui::Ui, expression: &CronExpression) {
+        ui.strong("Field");
+        ui.strong("Expression");
+        ui.strong("Values");
+        ui.end_row();
+        for field in expression.fields() {
+            ui.label(field.kind.name());
+            ui.monospace(&field.source);
+            ui.monospace(field.expanded());
+            ui.end_row();
+        }
+    }
+
+    fn upcoming_list(&mut self, ui: &mut egui::Ui, expression: &CronExpression) {
+        let now = Utc::now();
+        let key = (self.zone, self.count);
+        // kept until the first run has passed
+        let runs = match &self.upcoming {
+            Some((computed, runs))
+                if *computed == key && runs.first().is_none_or(|run| *run > now) =>
+            {
+                runs
+            }
+            _ => {
+                let runs = expression.upcoming(now, self.zone, self.count);
+                &self.upcoming.insert((key, runs)).1
+            }
+        };
+        if runs.is_empty() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "No fire times in the next years",
+            );
+            return;
+        }
+        for (index, run) in runs.iter().enumerate() {
+            ui.label(format!("{}", index + 1));
+            ui.monospace(run.format("%Y-%m-%d %H:%M:%S %:z  %a").to_string());
+            ui.label(DateTimeConverter::relative(*run, now.fixed_offset()));
+            ui.end_row();
+        }
+    }
+}
+
+impl ToolState for CronView {
+    fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    fn save_state(&self) -> Option<String> {
+        to_json(self)
+    }
+
+    fn load_state(&mut self, state: &str) -> ToolResult<()> {
+        *self = from_json(state)?;
+        self.parse();
+        Ok(())
+    }
+}
+
+impl ToolLogic for CronView {
+    fn process(&mut self) -> ToolResult<()> {
+        self.parse();
+        process_result(&self.err_msg)
+    }
+
+    fn validate(&self) -> bool {
+        !self.input.trim().is_empty()
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.input = input.trim().to_string();
+        self.parse();
+    }
+}
+
+impl ToolUI for CronView {
+    fn name(&self) -> &str {
+        "Cron Expression"
+    }
+
+    fn description(&self) -> &str {
+        "Explain a cron expression and list its next fire times."
+    }
+
+    fn icon(&self) -> &str {
+        "⏰"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Conversion
+    }
+
+    fn process_label(&self) -> &str {
+        "Explain"
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.heading("Cron expression");
+            ui.separator();
+            ui.label(
+                RichText::new(
+                    "Explain crontab, Kubernetes and Quartz cron expressions and preview the next runs",
+                )
+                .text_style(egui::TextStyle::Small),
+            );
+
+            ui.add_space(20.0);
+
+            ui.horizontal(|ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.input)
+                        .font(egui::TextStyle::Monospace)
+                        .desired_width(260.0)
+                        .hint_text("*/5 * * * *"),
+                );
+                if response.changed() {
+                    self.parse();
+                }
+
+                ui.add_space(15.0);
+                ui.label("Zone:");
+                DateTimeConverterView::zone_combo(
+                    ui,
+                    "cron zone",
+                    &mut self.zone,
+                    &mut self.zone_filter,
+                );
+                ui.add(egui::DragValue::new(&mut self.count).range(1..=100).prefix("next "));
+            });
+
+            ui.add_space(20.0);
+
+            if !self.err_msg.is_empty() {
+                ui.colored_label(ui.visuals().error_fg_color, &self.err_msg);
+            }
+
+            let Some(expression) = self.expression.clone() else {
+                return;
+            };
+
+            ui.label(RichText::new(expression.explain()).strong());
+            ui.label(
+                RichText::new(expression.dialect.to_string()).text_style(egui::TextStyle::Small),
+            );
+            ui.add_space(10.0);
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("cron fields")
+                    .num_columns(3)
+                    .spacing([10.0, 5.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        Self::field_list(ui, &expression);
+                    });
+
+                ui.add_space(15.0);
+                ui.label(RichText::new("Next runs").strong());
+                egui::Grid::new("cron upcoming")
+                    .num_columns(3)
+                    .spacing([10.0, 5.0])
+                    .show(ui, |ui| {
+                        self.upcoming_list(ui, &expression);
+                    });
+            });
+        });
+    }
+}
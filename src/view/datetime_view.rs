@@ -1,74 +1,124 @@
-use crate::model::DateTimeConverter;
+use crate::model::{
+    business_days, CalendarInfo, DateDifference, DateDuration, DateFormat, DateTimeConverter, Zone,
+};
 
-use super::View;
-use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use crate::tools::{
+    from_json, process_result, to_json, ToolCategory, ToolLogic, ToolResult, ToolState, ToolUI,
+};
+use chrono::{DateTime, FixedOffset, Utc};
 use egui::RichText;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DateTimeConverterView {
     pub input: String,
-    pub selected_format: String,
-    pub number: u64,
-    pub local_time: DateTime<Local>,
+    /// `None` lets [`DateFormat::detect`] pick the format.
+    pub format_override: Option<DateFormat>,
+    #[serde(skip)]
+    pub detected_format: Option<DateFormat>,
+    #[serde(skip)]
+    pub datetime: DateTime<FixedOffset>,
+    pub input_zone: Zone,
+    pub output_zone: Zone,
+    pub custom_pattern: String,
+    pub duration_input: String,
+    pub compare_input: String,
+    pub world_clock: Vec<Zone>,
+    pub new_clock_zone: Zone,
+    #[serde(skip)]
     pub err_msg: String,
+    #[serde(skip)]
+    pub warning: String,
+    #[serde(skip)]
+    zone_filter: String,
+}
+
+impl Default for DateTimeConverterView {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl DateTimeConverterView {
     pub fn new() -> Self {
         Self {
             input: String::new(),
-            selected_format: String::from("timestamp"),
-            number: 0,
+            format_override: None,
+            detected_format: None,
             err_msg: String::new(),
-            local_time: Local::now(),
+            warning: String::new(),
+            datetime: Utc::now().fixed_offset(),
+            input_zone: Zone::Local,
+            output_zone: Zone::Local,
+            custom_pattern: String::from("%A, %B %e %Y %H:%M:%S %Z"),
+            duration_input: String::new(),
+            compare_input: String::new(),
+            world_clock: ["UTC", "America/New_York", "Europe/London", "Asia/Tokyo"]
+                .iter()
+                .filter_map(|name| Zone::parse(name))
+                .collect(),
+            new_clock_zone: Zone::UTC,
+            zone_filter: String::new(),
         }
     }
 
     fn parse(&mut self) {
-        match self.selected_format.as_str() {
-            "timestamp" => self.timestamp_to_datetime(),
-            "datetime" => self.datetime_to_timestamp(),
-            _ => (),
-        };
-    }
-
-    fn timestamp_to_datetime(&mut self) {
         self.err_msg.clear();
+        self.warning.clear();
+        self.detected_format = None;
 
-        match self.input.trim().parse::<i64>() {
-            Ok(timestamp) => {
-                if let Some(datetime) = NaiveDateTime::from_timestamp_opt(timestamp, 0) {
-                    let local_time: DateTime<Local> = Local.from_utc_datetime(&datetime);
-                    self.local_time = local_time;
-                } else {
-                    self.err_msg = "Invalid timestamp ...".to_string();
-                }
-            }
-            Err(_) => {
-                self.err_msg = "Please enter a valid timestamp".to_string();
-            }
+        if self.input.trim().is_empty() {
+            return;
         }
-    }
 
-    fn datetime_to_timestamp(&mut self) {
-        self.err_msg.clear();
+        let parsed = match self.format_override {
+            Some(format) => format
+                .parse(&self.input, self.input_zone)
+                .map(|zoned| (format, zoned)),
+            None => DateFormat::detect(&self.input, self.input_zone).ok_or_else(|| {
+                "Unrecognized date format, please choose one explicitly".to_string()
+            }),
+        };
 
-        match NaiveDateTime::parse_from_str(self.input.trim(), "%Y-%m-%d %H:%M:%S") {
-            Ok(datetime) => {
-                let local_time = Local.from_utc_datetime(&datetime);
-                self.local_time = local_time
-            }
-            Err(_) => {
-                self.err_msg =
-                    "Please enter the date and time in the format：YYYY-MM-DD HH:mm:SS".to_string();
+        match parsed {
+            Ok((format, zoned)) => {
+                self.detected_format = Some(format);
+                self.datetime = zoned.datetime;
+                self.warning = zoned.warning.unwrap_or_default();
             }
+            Err(e) => self.err_msg = e,
         }
     }
 
-    fn formater_list(&self, ui: &mut egui::Ui) {
+    /// Searchable zone picker, returns `true` when the selection changed.
+    pub(crate) fn zone_combo(
+        ui: &mut egui::Ui,
+        id: &str,
+        zone: &mut Zone,
+        filter: &mut String,
+    ) -> bool {
+        let before = *zone;
+        egui::ComboBox::from_id_salt(id)
+            .selected_text(zone.name())
+            .width(180.0)
+            .show_ui(ui, |ui| {
+                ui.add(egui::TextEdit::singleline(filter).hint_text("Search zone..."));
+                let needle = filter.to_lowercase();
+                Zone::all()
+                    .filter(|z| z.name().to_lowercase().contains(&needle))
+                    .for_each(|z| {
+                        ui.selectable_value(zone, z, z.name());
+                    });
+            });
+        before != *zone
+    }
+
+    fn formater_list(&mut self, ui: &mut egui::Ui) {
+        let datetime = self.output_zone.from_utc(self.datetime.with_timezone(&Utc));
         DateTimeConverter::iter().for_each(|converter| {
             ui.label(converter.name());
-            let mut formatter_str = converter.formatter(self.local_time);
+            let mut formatter_str = converter.formatter(datetime);
             ui.text_edit_singleline(&mut formatter_str);
 
             // ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
@@ -80,11 +130,215 @@ impl DateTimeConverterView {
 
             ui.end_row();
         });
+
+        ui.label("Custom pattern");
+        ui.add(
+            egui::TextEdit::singleline(&mut self.custom_pattern)
+                .font(egui::TextStyle::Monospace)
+                .hint_text("strftime pattern, e.g. %Y-%m-%d"),
+        );
+        ui.end_row();
+
+        ui.label("");
+        match DateTimeConverter::custom(datetime, &self.custom_pattern) {
+            Ok(mut preview) => {
+                ui.text_edit_singleline(&mut preview);
+            }
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+        }
+        ui.end_row();
+    }
+
+    fn date_math(&mut self, ui: &mut egui::Ui) {
+        let datetime = self.output_zone.from_utc(self.datetime.with_timezone(&Utc));
+
+        egui::Grid::new("date math")
+            .num_columns(2)
+            .spacing([10.0, 5.0])
+            .show(ui, |ui| {
+                ui.label("Add duration");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.duration_input)
+                        .hint_text("+3d 4h, -1w, P1M2DT3H ..."),
+                );
+                ui.end_row();
+
+                if !self.duration_input.trim().is_empty() {
+                    ui.label("");
+                    match DateDuration::parse(&self.duration_input)
+                        .and_then(|d| d.apply(datetime, self.output_zone).map(|r| (d, r)))
+                    {
+                        Ok((duration, zoned)) => {
+                            let mut result = format!(
+                                "{}  ({})",
+                                DateTimeConverter::ISO8601.formatter(zoned.datetime),
+                                duration.to_iso()
+                            );
+                            ui.vertical(|ui| {
+                                ui.text_edit_singleline(&mut result);
+                                if let Some(warning) = zoned.warning {
+                                    ui.colored_label(ui.visuals().warn_fg_color, warning);
+                                }
+                            });
+                        }
+                        Err(e) => {
+                            ui.colored_label(ui.visuals().error_fg_color, e);
+                        }
+                    }
+                    ui.end_row();
+                }
+
+                ui.label("Compare with");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.compare_input)
+                        .hint_text("Another date, any supported format"),
+                );
+                ui.end_row();
+
+                if !self.compare_input.trim().is_empty() {
+                    match DateFormat::detect(&self.compare_input, self.input_zone) {
+                        Some((_, other)) => {
+                            let other = self
+                                .output_zone
+                                .from_utc(other.datetime.with_timezone(&Utc));
+                            let difference = DateDifference::between(datetime, other);
+                            ui.label("Difference");
+                            ui.strong(difference.human());
+                            ui.end_row();
+                            for (unit, value) in difference.units() {
+                                ui.label(unit);
+                                ui.monospace(format!("{:.4}", value));
+                                ui.end_row();
+                            }
+                            ui.label("Business days");
+                            ui.monospace(
+                                business_days(datetime.date_naive(), other.date_naive())
+                                    .to_string(),
+                            );
+                            ui.end_row();
+                        }
+                        None => {
+                            ui.label("");
+                            ui.colored_label(
+                                ui.visuals().error_fg_color,
+                                "Unrecognized date format",
+                            );
+                            ui.end_row();
+                        }
+                    }
+                }
+            });
+
+        ui.add_space(10.0);
+        let info = CalendarInfo::of(datetime.date_naive());
+        egui::Grid::new("calendar info")
+            .num_columns(2)
+            .spacing([10.0, 5.0])
+            .striped(true)
+            .show(ui, |ui| {
+                let rows = [
+                    ("Weekday", info.weekday.to_string()),
+                    (
+                        "ISO week",
+                        format!("{}-W{:02}", info.iso_year, info.iso_week),
+                    ),
+                    ("Day of year", info.day_of_year.to_string()),
+                    ("Quarter", format!("Q{}", info.quarter)),
+                    ("Leap year", info.leap_year.to_string()),
+                    ("Days in month", info.days_in_month.to_string()),
+                ];
+                for (label, value) in rows {
+                    ui.label(label);
+                    ui.monospace(value);
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn world_clock_list(&mut self, ui: &mut egui::Ui) {
+        let utc = self.datetime.with_timezone(&Utc);
+        let mut removed = None;
+        for (index, zone) in self.world_clock.iter().enumerate() {
+            ui.label(zone.name());
+            ui.monospace(
+                zone.from_utc(utc)
+                    .format("%Y-%m-%d %H:%M:%S %:z")
+                    .to_string(),
+            );
+            ui.label(zone.abbreviation(utc));
+            if ui.small_button("✖").clicked() {
+                removed = Some(index);
+            }
+            ui.end_row();
+        }
+        if let Some(index) = removed {
+            self.world_clock.remove(index);
+        }
     }
 }
 
-impl View for DateTimeConverterView {
-    fn render(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+impl ToolState for DateTimeConverterView {
+    fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    fn save_state(&self) -> Option<String> {
+        to_json(self)
+    }
+
+    fn load_state(&mut self, state: &str) -> ToolResult<()> {
+        *self = from_json(state)?;
+        self.parse();
+        Ok(())
+    }
+}
+
+impl ToolLogic for DateTimeConverterView {
+    fn process(&mut self) -> ToolResult<()> {
+        self.parse();
+        process_result(&self.err_msg)
+    }
+
+    fn validate(&self) -> bool {
+        !self.input.trim().is_empty()
+    }
+
+    /// The parsed time as RFC 3339.
+    fn output(&self) -> Option<String> {
+        self.detected_format.map(|_| self.datetime.to_rfc3339())
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.input = input.trim().to_string();
+        self.format_override = None;
+        self.parse();
+    }
+}
+
+impl ToolUI for DateTimeConverterView {
+    fn name(&self) -> &str {
+        "Date Time Converter"
+    }
+
+    fn description(&self) -> &str {
+        "Convert timestamps and dates between formats and time zones."
+    }
+
+    fn icon(&self) -> &str {
+        "🕓"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Conversion
+    }
+
+    fn process_label(&self) -> &str {
+        "Convert"
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
             ui.heading("Date-time converter");
             ui.separator();
@@ -106,47 +360,119 @@ impl View for DateTimeConverterView {
                 }
 
                 if self.input.is_empty() {
-                    self.local_time = Local::now();
+                    self.datetime = Utc::now().fixed_offset();
                 }
 
                 // 添加格式选择下拉框
+                let selected = self.format_override.map_or("Auto", |f| f.name());
+                let before = self.format_override;
                 egui::ComboBox::from_label("")
-                    .selected_text(&self.selected_format)
+                    .selected_text(selected)
                     .show_ui(ui, |ui| {
-                        ui.selectable_value(
-                            &mut self.selected_format,
-                            "timestamp".to_string(),
-                            "Timestamp",
-                        );
-                        ui.selectable_value(
-                            &mut self.selected_format,
-                            "datetime".to_string(),
-                            "DateTime",
-                        );
+                        ui.selectable_value(&mut self.format_override, None, "Auto");
+                        DateFormat::iter().for_each(|format| {
+                            ui.selectable_value(
+                                &mut self.format_override,
+                                Some(*format),
+                                format.name(),
+                            )
+                            .on_hover_text(format.example());
+                        });
                     });
+                if before != self.format_override {
+                    self.parse();
+                }
+            });
+
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.label("Input zone:");
+                if Self::zone_combo(
+                    ui,
+                    "input zone",
+                    &mut self.input_zone,
+                    &mut self.zone_filter,
+                ) {
+                    self.parse();
+                }
+                ui.add_space(15.0);
+                ui.label("Output zone:");
+                Self::zone_combo(
+                    ui,
+                    "output zone",
+                    &mut self.output_zone,
+                    &mut self.zone_filter,
+                );
             });
 
             ui.add_space(20.0);
 
             // 输出区域
             if !self.err_msg.is_empty() {
-                ui.colored_label(egui::Color32::RED, &self.err_msg);
+                ui.colored_label(ui.visuals().error_fg_color, &self.err_msg);
+            }
+            if let Some(format) = self.detected_format {
+                let source = if self.format_override.is_some() {
+                    "Parsed as"
+                } else {
+                    "Detected"
+                };
+                ui.label(
+                    RichText::new(format!("{}: {}", source, format.name()))
+                        .text_style(egui::TextStyle::Small),
+                );
             }
+            if !self.warning.is_empty() {
+                ui.colored_label(ui.visuals().warn_fg_color, &self.warning);
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                // formater list
+                let _column_widths = [60.0, ui.available_width() - 60.0, 60.0]; // 定义三列宽度
+                                                                                // 计算可用宽度（留出20px边距）
+                let available_width = ui.available_width() - 120.0;
+                // println!("available_width:{}",available_width);
+                egui::Grid::new("formatter list")
+                    .num_columns(2)
+                    .min_col_width(40.0) // 设置列最小宽度
+                    .max_col_width(available_width) // 设置最大宽度限制
+                    .spacing([10.0, 5.0]) // 增加水平间距
+                    // .striped(true)
+                    .show(ui, |ui| {
+                        self.formater_list(ui);
+                    });
+
+                ui.add_space(20.0);
+                egui::CollapsingHeader::new(RichText::new("Date math").strong())
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        self.date_math(ui);
+                    });
 
-            // formater list
-            let _column_widths = [60.0, ui.available_width() - 60.0, 60.0]; // 定义三列宽度
-                                                                            // 计算可用宽度（留出20px边距）
-            let available_width = ui.available_width() - 120.0;
-            // println!("available_width:{}",available_width);
-            egui::Grid::new("formatter list")
-                .num_columns(2)
-                .min_col_width(40.0) // 设置列最小宽度
-                .max_col_width(available_width) // 设置最大宽度限制
-                .spacing([10.0, 5.0]) // 增加水平间距
-                // .striped(true)
-                .show(ui, |ui| {
-                    self.formater_list(ui);
+                ui.add_space(20.0);
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("World clock").strong());
+                    Self::zone_combo(
+                        ui,
+                        "world clock zone",
+                        &mut self.new_clock_zone,
+                        &mut self.zone_filter,
+                    );
+                    if ui.button("Add").clicked()
+                        && !self.world_clock.contains(&self.new_clock_zone)
+                    {
+                        self.world_clock.push(self.new_clock_zone);
+                    }
                 });
+                egui::Grid::new("world clock")
+                    .num_columns(4)
+                    .spacing([10.0, 5.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        self.world_clock_list(ui);
+                    });
+            });
         });
     }
 }
@@ -1,17 +1,74 @@
-use std::f32;
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
 
-use egui::{Color32, FontId, RichText, TextFormat, Widget};
+use egui::{text::LayoutJob, Color32, FontId, RichText, TextFormat};
+use serde::{Deserialize, Serialize};
+use similar::ChangeTag;
 
-use crate::model::TextDifference;
+use crate::config::AppStyle;
+use crate::model::{
+    ConflictChoice, DiffGranularity, DiffLine, DiffOptions, DiffStats, EntryStatus, FileContent,
+    FolderDifference, FolderEntry, MergeChunk, PendingComparison, TextDifference, ThreeWayMerge,
+};
 
-use super::View;
+use crate::tools::{
+    from_json, to_json, ToolAction, ToolCategory, ToolError, ToolLogic, ToolResult, ToolState,
+    ToolUI,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+const MINIMAP_WIDTH: f32 = 12.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DifferenceView {
     pub input1: String,
     pub input2: String,
-    pub differs: Vec<(String, String)>,
+    pub granularity: DiffGranularity,
+    pub options: DiffOptions,
+    pub side_by_side: bool,
+    pub context_lines: usize,
+    #[serde(skip)]
+    pub lines: Vec<DiffLine>,
+    #[serde(skip)]
+    pub changes: Vec<(ChangeTag, String)>,
+    #[serde(skip)]
+    pub patch: String,
+    #[serde(skip)]
+    pub stats: DiffStats,
     pub compared: bool,
+    /// Change blocks of `lines`, for navigation and the minimap.
+    #[serde(skip)]
+    blocks: Vec<Range<usize>>,
+    #[serde(skip)]
+    current_block: usize,
+    #[serde(skip)]
+    scroll_to_block: bool,
+    /// Merges `input1` (ours) and `input2` (theirs) against `base`.
+    pub three_way: bool,
+    pub base: String,
+    #[serde(skip)]
+    pub merge: ThreeWayMerge,
+    #[serde(skip)]
+    pub choices: Vec<ConflictChoice>,
+    /// Files or folders to compare instead of typed text.
+    pub left_path: String,
+    pub right_path: String,
+    #[serde(skip)]
+    pub folder_entries: Vec<FolderEntry>,
+    #[serde(skip)]
+    comparing: Option<PendingComparison>,
+    #[serde(skip)]
+    pub file_msg: String,
+    #[serde(skip)]
+    selected_entry: Option<usize>,
+}
+
+impl Default for DifferenceView {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl DifferenceView {
@@ -19,77 +76,601 @@ impl DifferenceView {
         Self {
             input1: String::new(),
             input2: String::new(),
-            differs: Vec::new(),
+            granularity: DiffGranularity::Line,
+            options: DiffOptions::default(),
+            side_by_side: true,
+            context_lines: 3,
+            lines: Vec::new(),
+            changes: Vec::new(),
+            patch: String::new(),
+            stats: DiffStats::default(),
             compared: false,
+            blocks: Vec::new(),
+            current_block: 0,
+            scroll_to_block: false,
+            three_way: false,
+            left_path: String::new(),
+            right_path: String::new(),
+            folder_entries: Vec::new(),
+            comparing: None,
+            file_msg: String::new(),
+            selected_entry: None,
+            base: String::new(),
+            merge: ThreeWayMerge::default(),
+            choices: Vec::new(),
         }
     }
 
-    fn _create_colored_editor(
-        ui: &mut egui::Ui,
-        text: &mut String,
-        is_original: bool,
-        max_width: f32,
-        differs: &[(String, String)], // 通过参数传入差异数据
-    ) -> egui::Response {
-        // let differs = &self.differs;
-
-        let mut layouter = move |ui: &egui::Ui, _: &str, wrap_width: f32| {
-            let mut layout_job = egui::text::LayoutJob::default();
-
-            for (word, flag) in differs {
-                let color = match flag.as_str() {
-                    "-" if is_original => Color32::RED,
-                    "+" if !is_original => Color32::GREEN,
-                    _ => Color32::TRANSPARENT,
+    fn reset(&mut self) {
+        self.lines.clear();
+        self.changes.clear();
+        self.patch.clear();
+        self.stats = DiffStats::default();
+        self.blocks.clear();
+        self.current_block = 0;
+        self.merge = ThreeWayMerge::default();
+        self.choices.clear();
+    }
+
+    fn calculate_diff(&mut self) {
+        self.reset();
+        if self.three_way {
+            self.merge = ThreeWayMerge::merge(&self.base, &self.input1, &self.input2);
+            self.choices = vec![ConflictChoice::Unresolved; self.merge.conflicts().count()];
+            return;
+        }
+        if self.input1.is_empty() && self.input2.is_empty() {
+            return;
+        }
+
+        match self.granularity {
+            DiffGranularity::Line => {
+                self.lines = TextDifference::lines(&self.input1, &self.input2, &self.options);
+                self.blocks = TextDifference::blocks(&self.lines);
+                self.stats = DiffStats::of(&self.lines);
+            }
+            granularity => {
+                self.changes = TextDifference::changes(&self.input1, &self.input2, granularity);
+                self.stats.similarity =
+                    TextDifference::ratio(&self.input1, &self.input2, granularity);
+            }
+        }
+        self.patch = TextDifference::unified(
+            &self.input1,
+            &self.input2,
+            &self.options,
+            self.context_lines,
+            "original",
+            "modified",
+        );
+    }
+
+    fn open_paths(&mut self, ctx: &egui::Context) {
+        self.file_msg.clear();
+        self.folder_entries.clear();
+        self.selected_entry = None;
+        self.comparing = None;
+        let (left, right) = (Path::new(&self.left_path), Path::new(&self.right_path));
+
+        if left.is_dir() && right.is_dir() {
+            let ctx = ctx.clone();
+            self.comparing = Some(PendingComparison::start(
+                left.to_path_buf(),
+                right.to_path_buf(),
+                move || ctx.request_repaint(),
+            ));
+        } else if left.is_file() && right.is_file() {
+            self.load_files(Some(left.to_path_buf()), Some(right.to_path_buf()));
+        } else {
+            self.file_msg = "Choose two existing files or two folders".to_string();
+        }
+    }
+
+    /// Loads a file pair into the inputs, a missing side compares as empty.
+    fn load_files(&mut self, left: Option<PathBuf>, right: Option<PathBuf>) {
+        let read = |path: Option<PathBuf>| match path {
+            Some(path) => FolderDifference::read_file(&path),
+            None => Ok(FileContent::Text(String::new())),
+        };
+        let contents = read(left).and_then(|left| Ok((left, read(right)?)));
+        match contents {
+            Ok((FileContent::Text(left), FileContent::Text(right))) => {
+                self.file_msg.clear();
+                self.input1 = left;
+                self.input2 = right;
+                self.three_way = false;
+                self.compared = true;
+                self.calculate_diff();
+            }
+            Ok((left, right)) => {
+                self.compared = false;
+                self.reset();
+                self.file_msg = match (left, right) {
+                    (
+                        FileContent::Binary { hash: left, .. },
+                        FileContent::Binary { hash: right, .. },
+                    ) if left == right => "Binary files are identical".to_string(),
+                    _ => "Binary files differ".to_string(),
                 };
+            }
+            Err(e) => self.file_msg = e,
+        }
+    }
 
-                let should_display = (is_original && flag != "+") || (!is_original && flag != "-");
-
-                if should_display {
-                    layout_job.append(
-                        word,
-                        0.0,
-                        TextFormat {
-                            font_id: FontId::monospace(14.0),
-                            color,
-                            ..Default::default()
-                        },
-                    );
-                    layout_job.append(" ", 0.0, TextFormat::default());
+    fn path_picker(ui: &mut egui::Ui, label: &str, path: &mut String) {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            ui.add(egui::TextEdit::singleline(path).desired_width(300.0));
+            if ui.button("File…").clicked() {
+                if let Some(file) = rfd::FileDialog::new().pick_file() {
+                    *path = file.display().to_string();
+                }
+            }
+            if ui.button("Folder…").clicked() {
+                if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                    *path = folder.display().to_string();
                 }
             }
+        });
+    }
+
+    fn files_and_folders(&mut self, ui: &mut egui::Ui) {
+        Self::path_picker(ui, "Left: ", &mut self.left_path);
+        Self::path_picker(ui, "Right:", &mut self.right_path);
+        if ui.button("Open").clicked() {
+            self.open_paths(ui.ctx());
+        }
+        if let Some(result) = self.comparing.as_ref().and_then(|c| c.result()).cloned() {
+            self.comparing = None;
+            match result {
+                Ok(entries) => self.folder_entries = entries,
+                Err(e) => self.file_msg = e,
+            }
+        }
+        if self.comparing.is_some() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Comparing folders…");
+            });
+        }
+        if !self.file_msg.is_empty() {
+            ui.colored_label(ui.visuals().warn_fg_color, &self.file_msg);
+        }
+        if self.folder_entries.is_empty() {
+            return;
+        }
 
-            layout_job.wrap.max_width = wrap_width;
-            ui.fonts(|f| f.layout_job(layout_job))
+        let style = AppStyle::current(ui.ctx());
+        let mut opened = None;
+        egui::ScrollArea::vertical()
+            .id_salt("folder entries")
+            .max_height(160.0)
+            .show(ui, |ui| {
+                egui::Grid::new("folder entries")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (index, entry) in self.folder_entries.iter().enumerate() {
+                            let color = match entry.status {
+                                EntryStatus::OnlyLeft => style.diff_delete,
+                                EntryStatus::OnlyRight => style.diff_insert,
+                                EntryStatus::Identical => style.muted,
+                                EntryStatus::Different => style.warning,
+                                EntryStatus::Unreadable => style.error,
+                            };
+                            ui.colored_label(color, entry.status.name());
+                            let selected = self.selected_entry == Some(index);
+                            if ui
+                                .selectable_label(selected, entry.path.display().to_string())
+                                .clicked()
+                            {
+                                opened = Some(index);
+                            }
+                            match &entry.error {
+                                Some(error) => ui.colored_label(style.error, error),
+                                None => ui.label(if entry.binary { "binary" } else { "" }),
+                            };
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if let Some(index) = opened {
+            self.selected_entry = Some(index);
+            let entry = &self.folder_entries[index];
+            let left = (entry.status != EntryStatus::OnlyRight)
+                .then(|| Path::new(&self.left_path).join(&entry.path));
+            let right = (entry.status != EntryStatus::OnlyLeft)
+                .then(|| Path::new(&self.right_path).join(&entry.path));
+            self.load_files(left, right);
+        }
+    }
+
+    fn format(color: Color32, background: Color32) -> TextFormat {
+        TextFormat {
+            font_id: FontId::monospace(14.0),
+            color,
+            background,
+            ..Default::default()
+        }
+    }
+
+    /// Lays out one diff line, changed words get a highlighted background.
+    fn line_job(ui: &egui::Ui, line: &DiffLine) -> LayoutJob {
+        let style = AppStyle::current(ui.ctx());
+        let text_color = ui.visuals().text_color();
+        let (color, emphasis) = match line.tag {
+            ChangeTag::Delete => (style.diff_delete, style.diff_delete_background),
+            ChangeTag::Insert => (style.diff_insert, style.diff_insert_background),
+            ChangeTag::Equal => (text_color, Color32::TRANSPARENT),
         };
 
-        egui::TextEdit::multiline(text)
-            .font(egui::TextStyle::Monospace)
-            .code_editor()
-            .desired_rows(20)
-            .desired_width(max_width)
-            .layouter(&mut layouter)
-            .ui(ui)
+        let mut job = LayoutJob::default();
+        for (emphasized, text) in &line.segments {
+            let text = text.trim_end_matches(['\r', '\n']);
+            let format = if *emphasized {
+                Self::format(style.foreground, emphasis)
+            } else {
+                Self::format(color, Color32::TRANSPARENT)
+            };
+            job.append(text, 0.0, format);
+        }
+        job
     }
 
-    fn calculate_diff(&mut self) {
-        self.differs = if !self.input1.is_empty() && !self.input2.is_empty() {
-            TextDifference::differ(&self.input1, &self.input2)
-                .into_iter()
-                // 过滤有效差异
-                .filter(|(_, flag)| flag == "-" || flag == "+")
-                // .filter(|(val, _)| val != "\n")
-                .collect()
+    fn line_number(ui: &mut egui::Ui, index: Option<usize>) {
+        let number = index.map(|i| (i + 1).to_string()).unwrap_or_default();
+        let color = AppStyle::current(ui.ctx()).muted;
+        ui.label(RichText::new(number).monospace().color(color));
+    }
+
+    /// Renders a line diff, shared with the patch view. The line at `focus`
+    /// is scrolled into view.
+    pub(crate) fn show_lines(
+        ui: &mut egui::Ui,
+        lines: &[DiffLine],
+        side_by_side: bool,
+        focus: Option<usize>,
+    ) {
+        if side_by_side {
+            Self::side_by_side_lines(ui, lines, focus.map(|i| &lines[i]));
         } else {
-            Vec::new()
-        };
+            Self::unified_lines(ui, lines, focus);
+        }
+    }
+
+    fn unified_lines(ui: &mut egui::Ui, lines: &[DiffLine], focus: Option<usize>) {
+        egui::Grid::new(ui.next_auto_id())
+            .num_columns(4)
+            .spacing([8.0, 2.0])
+            .show(ui, |ui| {
+                for (index, line) in lines.iter().enumerate() {
+                    Self::line_number(ui, line.old_index);
+                    Self::line_number(ui, line.new_index);
+                    ui.monospace(line.sign());
+                    let response = ui.label(Self::line_job(ui, line));
+                    if focus == Some(index) {
+                        response.scroll_to_me(Some(egui::Align::Center));
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn side_by_side_lines(ui: &mut egui::Ui, lines: &[DiffLine], focus: Option<&DiffLine>) {
+        let column_width = (ui.available_width() - 80.0) / 2.0;
+        egui::Grid::new(ui.next_auto_id())
+            .num_columns(4)
+            .spacing([8.0, 2.0])
+            .min_col_width(20.0)
+            .max_col_width(column_width)
+            .show(ui, |ui| {
+                for row in TextDifference::side_by_side(lines) {
+                    for (side, old) in [(&row.old, true), (&row.new, false)] {
+                        match side {
+                            Some(line) => {
+                                let index = if old { line.old_index } else { line.new_index };
+                                Self::line_number(ui, index);
+                                let response = ui.label(Self::line_job(ui, line));
+                                if focus == Some(line) {
+                                    response.scroll_to_me(Some(egui::Align::Center));
+                                }
+                            }
+                            None => {
+                                ui.label("");
+                                ui.label("");
+                            }
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn inline_changes(&self, ui: &mut egui::Ui) {
+        let style = AppStyle::current(ui.ctx());
+        let text_color = ui.visuals().text_color();
+        let mut job = LayoutJob::default();
+        for (tag, text) in &self.changes {
+            let mut format = match tag {
+                ChangeTag::Delete => Self::format(style.foreground, style.diff_delete_background),
+                ChangeTag::Insert => Self::format(style.foreground, style.diff_insert_background),
+                ChangeTag::Equal => Self::format(text_color, Color32::TRANSPARENT),
+            };
+            if *tag == ChangeTag::Delete {
+                format.strikethrough = egui::Stroke::new(1.0, style.diff_delete);
+            }
+            job.append(text, 0.0, format);
+        }
+        job.wrap.max_width = ui.available_width();
+        ui.label(job);
+    }
+
+    /// Counts, similarity and previous/next change buttons.
+    fn summary(&mut self, ui: &mut egui::Ui) {
+        if self.granularity == DiffGranularity::Line {
+            let stats = self.stats;
+            let style = AppStyle::current(ui.ctx());
+            ui.label(RichText::new(format!("+{}", stats.insertions)).color(style.diff_insert));
+            ui.label(RichText::new(format!("-{}", stats.deletions)).color(style.diff_delete));
+            ui.label(format!("~{} changed", stats.changed));
+        }
+        ui.label(format!("{:.1}% similar", self.stats.similarity * 100.0));
 
-        println!("{:?}", self.differs);
+        if self.blocks.is_empty() {
+            return;
+        }
+        ui.separator();
+        if ui
+            .small_button("◀")
+            .on_hover_text("Previous change")
+            .clicked()
+        {
+            self.current_block = self
+                .current_block
+                .checked_sub(1)
+                .unwrap_or(self.blocks.len() - 1);
+            self.scroll_to_block = true;
+        }
+        ui.label(format!(
+            "{} / {}",
+            self.current_block + 1,
+            self.blocks.len()
+        ));
+        if ui.small_button("▶").on_hover_text("Next change").clicked() {
+            self.current_block = (self.current_block + 1) % self.blocks.len();
+            self.scroll_to_block = true;
+        }
+    }
+
+    /// Thin gutter marking where the changes are, clicking jumps to the
+    /// nearest one.
+    fn minimap(&mut self, ui: &mut egui::Ui, height: f32) {
+        let (rect, response) =
+            ui.allocate_exact_size(egui::vec2(MINIMAP_WIDTH, height), egui::Sense::click());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+        let style = AppStyle::current(ui.ctx());
+
+        let total = self.lines.len() as f32;
+        let mark = (rect.height() / total).max(2.0);
+        for (index, line) in self.lines.iter().enumerate() {
+            let color = match line.tag {
+                ChangeTag::Delete => style.diff_delete,
+                ChangeTag::Insert => style.diff_insert,
+                ChangeTag::Equal => continue,
+            };
+            let top = rect.top() + rect.height() * index as f32 / total;
+            painter.rect_filled(
+                egui::Rect::from_min_size(
+                    egui::pos2(rect.left(), top),
+                    egui::vec2(rect.width(), mark),
+                ),
+                0.0,
+                color,
+            );
+        }
+        if let Some(block) = self.blocks.get(self.current_block) {
+            let top = rect.top() + rect.height() * block.start as f32 / total;
+            let bottom = rect.top() + rect.height() * block.end as f32 / total;
+            painter.rect_stroke(
+                egui::Rect::from_x_y_ranges(rect.x_range(), top..=bottom.max(top + mark)),
+                0.0,
+                ui.visuals().selection.stroke,
+            );
+        }
+
+        if let Some(pos) = response
+            .interact_pointer_pos()
+            .filter(|_| response.clicked())
+        {
+            let line = ((pos.y - rect.top()) / rect.height() * total) as usize;
+            let nearest = self
+                .blocks
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, block)| {
+                    if line < block.start {
+                        block.start - line
+                    } else {
+                        line.saturating_sub(block.end - 1)
+                    }
+                })
+                .map(|(index, _)| index);
+            if let Some(index) = nearest {
+                self.current_block = index;
+                self.scroll_to_block = true;
+            }
+        }
+    }
+
+    fn merge_result(&mut self, ui: &mut egui::Ui) {
+        let result = self.merge.result(&self.choices);
+        let style = AppStyle::current(ui.ctx());
+        let unresolved = self
+            .choices
+            .iter()
+            .filter(|c| **c == ConflictChoice::Unresolved)
+            .count();
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "{} conflicts, {} unresolved",
+                self.choices.len(),
+                unresolved
+            ));
+            if ui.button("Copy merged result").clicked() {
+                ui.ctx().copy_text(result.clone());
+            }
+        });
+
+        egui::ScrollArea::vertical()
+            .auto_shrink(false)
+            .show(ui, |ui| {
+                let mut choices = self.choices.iter_mut().enumerate();
+                for chunk in &self.merge.chunks {
+                    let conflict = match chunk {
+                        MergeChunk::Clean(text) => {
+                            ui.monospace(text.trim_end_matches('\n'));
+                            continue;
+                        }
+                        MergeChunk::Conflict(conflict) => conflict,
+                    };
+                    let Some((index, choice)) = choices.next() else {
+                        break;
+                    };
+                    egui::Frame::group(ui.style()).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.colored_label(
+                                ui.visuals().warn_fg_color,
+                                format!("Conflict #{}", index + 1),
+                            );
+                            for option in [
+                                ConflictChoice::Unresolved,
+                                ConflictChoice::Ours,
+                                ConflictChoice::Theirs,
+                                ConflictChoice::Both,
+                            ] {
+                                ui.radio_value(choice, option, option.name());
+                            }
+                        });
+                        ui.columns(2, |columns| {
+                            columns[0].label(
+                                RichText::new(conflict.ours.trim_end_matches('\n'))
+                                    .monospace()
+                                    .color(style.diff_delete),
+                            );
+                            columns[1].label(
+                                RichText::new(conflict.theirs.trim_end_matches('\n'))
+                                    .monospace()
+                                    .color(style.diff_insert),
+                            );
+                        });
+                    });
+                }
+
+                ui.add_space(10.0);
+                egui::CollapsingHeader::new("Merged result").show(ui, |ui| {
+                    let mut result = result.as_str();
+                    ui.add(
+                        egui::TextEdit::multiline(&mut result)
+                            .font(egui::TextStyle::Monospace)
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+            });
+    }
+}
+
+impl ToolState for DifferenceView {
+    fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    fn save_state(&self) -> Option<String> {
+        to_json(self)
+    }
+
+    fn load_state(&mut self, state: &str) -> ToolResult<()> {
+        *self = from_json(state)?;
+        if self.compared {
+            self.calculate_diff();
+        }
+        Ok(())
+    }
+}
+
+impl ToolLogic for DifferenceView {
+    fn process(&mut self) -> ToolResult<()> {
+        self.compared = true;
+        self.calculate_diff();
+        Ok(())
+    }
+
+    fn validate(&self) -> bool {
+        !self.input1.is_empty() || !self.input2.is_empty() || self.three_way
+    }
+
+    fn actions(&self) -> Vec<ToolAction> {
+        vec![ToolAction {
+            id: "swap",
+            name: "Swap sides",
+        }]
+    }
+
+    fn run_action(&mut self, id: &str) -> ToolResult<()> {
+        match id {
+            "swap" => {
+                std::mem::swap(&mut self.input1, &mut self.input2);
+                std::mem::swap(&mut self.left_path, &mut self.right_path);
+                if self.compared {
+                    self.calculate_diff();
+                }
+                Ok(())
+            }
+            _ => Err(ToolError::ProcessError(format!("unknown action {}", id))),
+        }
+    }
+
+    /// The merged text in three-way mode, the unified diff otherwise.
+    fn output(&self) -> Option<String> {
+        if !self.compared {
+            return None;
+        }
+        if self.three_way {
+            return Some(self.merge.result(&self.choices));
+        }
+        Some(self.patch.clone()).filter(|patch| !patch.is_empty())
+    }
+
+    /// Fills the left side, the text to compare against goes on the right.
+    fn set_input(&mut self, input: &str) {
+        self.input1 = input.to_string();
     }
 }
 
-impl View for DifferenceView {
-    fn render(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+impl ToolUI for DifferenceView {
+    fn name(&self) -> &str {
+        "Text Difference"
+    }
+
+    fn description(&self) -> &str {
+        "Compare two texts, files or folders, or merge three texts."
+    }
+
+    fn icon(&self) -> &str {
+        "⚖"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Text
+    }
+
+    fn process_label(&self) -> &str {
+        "Compare"
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
             ui.heading("Text Difference");
             ui.separator();
@@ -100,6 +681,36 @@ impl View for DifferenceView {
             ui.add_space(20.0);
 
             ui.horizontal(|ui| {
+                let mut changed = false;
+                ui.label("Granularity:");
+                egui::ComboBox::from_id_salt("diff granularity")
+                    .selected_text(self.granularity.name())
+                    .show_ui(ui, |ui| {
+                        DiffGranularity::iter().for_each(|granularity| {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut self.granularity,
+                                    *granularity,
+                                    granularity.name(),
+                                )
+                                .changed();
+                        });
+                    });
+                ui.add_enabled(
+                    self.granularity == DiffGranularity::Line,
+                    egui::Checkbox::new(&mut self.side_by_side, "Side by side"),
+                );
+                changed |= ui
+                    .checkbox(&mut self.three_way, "Three-way merge")
+                    .changed();
+                ui.label("Context lines:");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut self.context_lines).range(0..=20))
+                    .changed();
+                if changed && self.compared {
+                    self.calculate_diff();
+                }
+
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
                     if !self.compared {
                         if ui
@@ -109,97 +720,137 @@ impl View for DifferenceView {
                             self.calculate_diff();
                             self.compared = true;
                         }
-                    } else {
-                        if ui
-                            .link(RichText::new("Reset").text_style(egui::TextStyle::Button))
-                            .clicked()
-                        {
-                            self.compared = false;
-                            self.differs.clear();
-                        }
+                    } else if ui
+                        .link(RichText::new("Reset").text_style(egui::TextStyle::Button))
+                        .clicked()
+                    {
+                        self.compared = false;
+                        self.reset();
                     }
                 });
                 ui.add_space(18.0);
             });
 
+            ui.add_enabled_ui(self.granularity == DiffGranularity::Line, |ui| {
+                let before = self.options;
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("Ignore:");
+                    let options = &mut self.options;
+                    ui.checkbox(&mut options.ignore_whitespace_change, "Whitespace changes");
+                    ui.checkbox(&mut options.ignore_all_whitespace, "All whitespace");
+                    ui.checkbox(&mut options.ignore_blank_lines, "Blank lines");
+                    ui.checkbox(&mut options.ignore_case, "Case");
+                    ui.checkbox(&mut options.ignore_line_endings, "Line endings");
+                });
+                if before != self.options && self.compared {
+                    self.calculate_diff();
+                }
+            });
+            egui::CollapsingHeader::new("Files and folders").show(ui, |ui| {
+                self.files_and_folders(ui);
+            });
+            ui.add_space(8.0);
+
             ui.horizontal(|ui| {
                 let max_width = ui.available_width();
+                let width = if self.three_way { 0.3 } else { 0.46 };
+
+                let res0 = self.three_way.then(|| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.base)
+                            .font(egui::TextStyle::Monospace)
+                            .hint_text("Base")
+                            .desired_rows(12)
+                            .desired_width(max_width * width),
+                    )
+                });
 
                 let res1 = ui.add(
                     egui::TextEdit::multiline(&mut self.input1)
                         .font(egui::TextStyle::Monospace)
-                        .desired_rows(20)
-                        .desired_width(max_width * 0.46),
+                        .hint_text(if self.three_way { "Ours" } else { "" })
+                        .desired_rows(12)
+                        .desired_width(max_width * width),
                 );
 
                 let res2 = ui.add(
                     egui::TextEdit::multiline(&mut self.input2)
                         .font(egui::TextStyle::Monospace)
-                        .desired_rows(20)
-                        .desired_width(max_width * 0.46),
+                        .hint_text(if self.three_way { "Theirs" } else { "" })
+                        .desired_rows(12)
+                        .desired_width(max_width * width),
                 );
 
-                if res1.changed() || res2.changed() {
+                let base_changed = res0.is_some_and(|r| r.changed());
+                if self.compared && (base_changed || res1.changed() || res2.changed()) {
                     self.calculate_diff();
                 }
             });
 
             ui.vertical(|ui| {
                 ui.separator();
-                ui.label(RichText::new("Compare Result :").strong());
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("Compare Result :").strong());
+                    if self.compared && !self.three_way {
+                        self.summary(ui);
+                    }
+                    if !self.patch.is_empty() && ui.button("Copy unified diff").clicked() {
+                        ui.ctx().copy_text(self.patch.clone());
+                    }
+                });
 
-                egui::ScrollArea::vertical()
-                    .auto_shrink(false)
-                    .show(ui, |ui| {
-                        let mut layouter = |ui: &egui::Ui, _: &str, wrap_width: f32| {
-                            let mut layout_job = egui::text::LayoutJob::default();
-                            for (text, flag) in &self.differs {
-                                let color = match flag.as_str() {
-                                    "-" => Color32::LIGHT_RED,
-                                    "+" => Color32::LIGHT_GREEN,
-                                    _ => Color32::GRAY,
-                                };
-
-                                layout_job.append(
-                                    text,
-                                    0.0,
-                                    TextFormat {
-                                        font_id: FontId::monospace(14.0),
-                                        background: color,
-                                        ..Default::default()
-                                    },
-                                );
-
-                                // 显式添加换行符
-                                layout_job.append(
-                                    "\n",
-                                    0.0,
-                                    TextFormat {
-                                        font_id: FontId::monospace(14.0),
-                                        ..Default::default()
-                                    },
-                                );
-                            }
+                if self.three_way {
+                    if self.compared {
+                        self.merge_result(ui);
+                    }
+                    return;
+                }
 
-                            layout_job.wrap.max_width = wrap_width;
-                            ui.fonts(|f| f.layout_job(layout_job))
-                        };
+                let show_minimap =
+                    self.granularity == DiffGranularity::Line && !self.lines.is_empty();
+                let height = ui.available_height();
+                let width = ui.available_width()
+                    - if show_minimap {
+                        MINIMAP_WIDTH + ui.spacing().item_spacing.x
+                    } else {
+                        0.0
+                    };
+                let focus = self
+                    .scroll_to_block
+                    .then(|| self.blocks.get(self.current_block).map(|b| b.start))
+                    .flatten();
+                self.scroll_to_block = false;
 
-                        let mut text = self
-                            .differs
-                            .iter()
-                            .map(|(w, f)| w.to_string())
-                            .collect::<Vec<String>>()
-                            .join("\n");
+                ui.horizontal_top(|ui| {
+                    ui.allocate_ui(egui::vec2(width, height), |ui| {
+                        egui::ScrollArea::vertical()
+                            .auto_shrink(false)
+                            .show(ui, |ui| {
+                                match self.granularity {
+                                    DiffGranularity::Line => {
+                                        Self::show_lines(ui, &self.lines, self.side_by_side, focus)
+                                    }
+                                    _ => self.inline_changes(ui),
+                                }
 
-                        egui::TextEdit::multiline(&mut text)
-                            .font(egui::TextStyle::Monospace)
-                            .code_editor()
-                            .desired_rows(10)
-                            .desired_width(f32::INFINITY)
-                            .layouter(&mut layouter)
-                            .ui(ui);
+                                if !self.patch.is_empty() {
+                                    ui.add_space(10.0);
+                                    egui::CollapsingHeader::new("Unified diff").show(ui, |ui| {
+                                        let mut patch = self.patch.as_str();
+                                        ui.add(
+                                            egui::TextEdit::multiline(&mut patch)
+                                                .font(egui::TextStyle::Monospace)
+                                                .code_editor()
+                                                .desired_width(f32::INFINITY),
+                                        );
+                                    });
+                                }
+                            });
                     });
+                    if show_minimap {
+                        self.minimap(ui, height);
+                    }
+                });
             });
         });
     }
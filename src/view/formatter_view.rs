@@ -1,22 +1,35 @@
 use egui::RichText;
+use serde::{Deserialize, Serialize};
 
+use super::syntax;
 use crate::model::{sql_formatter::SqlFormatter, JsonFormatter};
 
-use super::View;
+use crate::tools::{
+    from_json, process_result, to_json, ToolAction, ToolCategory, ToolError, ToolLogic, ToolResult,
+    ToolState, ToolUI,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct FormatterView {
     pub input: String,
+    #[serde(skip)]
     pub err_msg: String,
     formatter_type: FormatterType,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 enum FormatterType {
     JSON,
     SQL,
 }
 
+impl Default for FormatterView {
+    fn default() -> Self {
+        Self::new("json")
+    }
+}
+
 impl FormatterView {
     pub fn new(formatter_type: &str) -> Self {
         let formatter_type = match formatter_type {
@@ -28,7 +41,7 @@ impl FormatterView {
         Self {
             input: String::new(),
             err_msg: String::new(),
-            formatter_type: formatter_type,
+            formatter_type,
         }
     }
 
@@ -46,17 +59,6 @@ impl FormatterView {
         }
     }
 
-    pub fn description(&self) -> &str {
-        match self.formatter_type {
-            FormatterType::JSON => {
-                "Prettify your JSON string into a friendly, human-readable format."
-            }
-            FormatterType::SQL => {
-                "Format and prettify your SQL queries online (it supports various SQL dialects)."
-            }
-        }
-    }
-
     pub fn hint_text(&self) -> &str {
         match self.formatter_type {
             FormatterType::JSON => "Enter your Json ...",
@@ -75,16 +77,104 @@ impl FormatterView {
             Err(e) => self.err_msg = e.to_string(),
         }
     }
+
+    pub fn minify(&mut self) {
+        match JsonFormatter::minify_json(&self.input) {
+            Ok(r) => self.input = r,
+            Err(e) => self.err_msg = e.to_string(),
+        }
+    }
 }
 
-impl View for FormatterView {
-    fn render(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
-        let Self {
-            input,
-            err_msg,
-            formatter_type,
-        } = self;
+impl ToolState for FormatterView {
+    fn clear(&mut self) {
+        self.input.clear();
+        self.err_msg.clear();
+    }
+
+    fn save_state(&self) -> Option<String> {
+        to_json(self)
+    }
+
+    fn load_state(&mut self, state: &str) -> ToolResult<()> {
+        *self = from_json(state)?;
+        Ok(())
+    }
+}
+
+impl ToolLogic for FormatterView {
+    fn process(&mut self) -> ToolResult<()> {
+        self.err_msg.clear();
+        self.formatter();
+        process_result(&self.err_msg)
+    }
+
+    fn validate(&self) -> bool {
+        !self.input.trim().is_empty()
+    }
+
+    fn actions(&self) -> Vec<ToolAction> {
+        match self.formatter_type {
+            FormatterType::JSON => vec![ToolAction {
+                id: "minify",
+                name: "Minify",
+            }],
+            FormatterType::SQL => Vec::new(),
+        }
+    }
+
+    fn run_action(&mut self, id: &str) -> ToolResult<()> {
+        match (self.formatter_type, id) {
+            (FormatterType::JSON, "minify") => {
+                self.err_msg.clear();
+                self.minify();
+                process_result(&self.err_msg)
+            }
+            _ => Err(ToolError::ProcessError(format!("unknown action {}", id))),
+        }
+    }
+
+    fn output(&self) -> Option<String> {
+        Some(self.input.clone()).filter(|input| !input.is_empty())
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.input = input.to_string();
+    }
+}
+
+impl ToolUI for FormatterView {
+    fn name(&self) -> &str {
+        self.title()
+    }
+
+    fn description(&self) -> &str {
+        match self.formatter_type {
+            FormatterType::JSON => {
+                "Prettify your JSON string into a friendly, human-readable format."
+            }
+            FormatterType::SQL => {
+                "Format and prettify your SQL queries online (it supports various SQL dialects)."
+            }
+        }
+    }
+
+    fn icon(&self) -> &str {
+        match self.formatter_type {
+            FormatterType::JSON => "📄",
+            FormatterType::SQL => "🗄",
+        }
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Formatting
+    }
+
+    fn process_label(&self) -> &str {
+        "Format"
+    }
 
+    fn render(&mut self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
             ui.heading(self.title());
             ui.separator();
@@ -101,22 +191,14 @@ impl View for FormatterView {
 
             ui.add_space(10.0);
 
-            let theme =
-                egui_extras::syntax_highlighting::CodeTheme::from_memory(ui.ctx(), ui.style());
-
             let language = match self.formatter_type {
                 FormatterType::JSON => "json",
                 FormatterType::SQL => "sql",
             };
 
             let mut layouter = |ui: &egui::Ui, string: &str, wrap_width: f32| {
-                let mut layout_job = egui_extras::syntax_highlighting::highlight(
-                    ui.ctx(),
-                    ui.style(),
-                    &theme,
-                    string,
-                    language,
-                );
+                let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+                let mut layout_job = syntax::highlight(ui.ctx(), &font_id, string, language);
                 layout_job.wrap.max_width = wrap_width;
                 ui.fonts(|f| f.layout_job(layout_job))
             };
@@ -1,17 +1,23 @@
-use egui::{Color32, Painter, Pos2, Rect, Sense, Vec2};
+use egui::{Painter, Pos2, Rect, Sense, Vec2};
 use std::time::{Duration, Instant};
 
-use super::View;
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppStyle;
+use crate::tools::{from_json, to_json, ToolCategory, ToolLogic, ToolResult, ToolState, ToolUI};
 
 // 游戏状态
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GameOfLifeView {
     grid: Vec<Vec<bool>>, // 细胞网格
     width: usize,         // 网格宽度
     height: usize,        // 网格高度
     cell_size: f32,       // 细胞显示大小
-    running: bool,        // 是否正在运行
+    #[serde(skip)]
+    running: bool, // 是否正在运行
+    #[serde(skip)]
     last_update: Instant, // 上次更新时间
     update_interval: f32, // 更新间隔（秒）
 }
@@ -93,8 +99,9 @@ impl GameOfLifeView {
     }
 
     fn draw_grid(&self, painter: &Painter, rect: Rect) {
+        let style = AppStyle::current(painter.ctx());
         // 绘制背景
-        painter.rect_filled(rect, 0.0, Color32::from_gray(20));
+        painter.rect_filled(rect, 0.0, painter.ctx().style().visuals.extreme_bg_color);
 
         // 绘制细胞
         for x in 0..self.width {
@@ -108,14 +115,14 @@ impl GameOfLifeView {
                     painter.rect_filled(
                         Rect::from_min_size(pos, Vec2::splat(self.cell_size - 1.0)),
                         2.0,
-                        Color32::from_rgb(100, 200, 100),
+                        style.success,
                     );
                 }
             }
         }
 
         // 绘制网格线
-        let stroke = egui::Stroke::new(0.5, Color32::from_gray(50));
+        let stroke = egui::Stroke::new(0.5, style.muted.gamma_multiply(0.3));
         for x in 0..=self.width {
             let x = rect.left() + x as f32 * self.cell_size;
             painter.line_segment(
@@ -133,8 +140,56 @@ impl GameOfLifeView {
     }
 }
 
-impl View for GameOfLifeView {
-    fn render(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+impl ToolState for GameOfLifeView {
+    fn clear(&mut self) {
+        self.grid = vec![vec![false; self.height]; self.width];
+        self.running = false;
+    }
+
+    fn save_state(&self) -> Option<String> {
+        to_json(self)
+    }
+
+    fn load_state(&mut self, state: &str) -> ToolResult<()> {
+        *self = from_json(state)?;
+        Ok(())
+    }
+}
+
+impl ToolLogic for GameOfLifeView {
+    /// Advances one generation.
+    fn process(&mut self) -> ToolResult<()> {
+        self.next_generation();
+        Ok(())
+    }
+
+    fn validate(&self) -> bool {
+        true
+    }
+}
+
+impl ToolUI for GameOfLifeView {
+    fn name(&self) -> &str {
+        "Game of Life"
+    }
+
+    fn description(&self) -> &str {
+        "Conway's Game of Life, click cells to toggle them."
+    }
+
+    fn icon(&self) -> &str {
+        "🎮"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Fun
+    }
+
+    fn process_label(&self) -> &str {
+        "Step"
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             if ui
                 .button(if self.running {
@@ -1,24 +1,117 @@
 use egui::RichText;
+use serde::{Deserialize, Serialize};
 
 use crate::model::HashAlgorithm;
 
-use super::View;
+use crate::tools::{
+    from_json, to_json, ToolAction, ToolCategory, ToolError, ToolLogic, ToolResult, ToolState,
+    ToolUI,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
 pub struct HashView {
     pub input: String,
+    /// Picked by an algorithm's action, the output is then only that digest.
+    #[serde(skip)]
+    only: Option<HashAlgorithm>,
 }
 
 impl HashView {
     pub fn new() -> Self {
         Self {
             input: String::new(),
+            only: None,
         }
     }
 }
 
-impl View for HashView {
-    fn render(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+impl ToolState for HashView {
+    fn clear(&mut self) {
+        self.input.clear();
+        self.only = None;
+    }
+
+    fn save_state(&self) -> Option<String> {
+        to_json(self)
+    }
+
+    fn load_state(&mut self, state: &str) -> ToolResult<()> {
+        *self = from_json(state)?;
+        Ok(())
+    }
+}
+
+impl ToolLogic for HashView {
+    /// Hashes are computed while rendering, there is nothing to prepare.
+    fn process(&mut self) -> ToolResult<()> {
+        Ok(())
+    }
+
+    fn validate(&self) -> bool {
+        !self.input.is_empty()
+    }
+
+    fn actions(&self) -> Vec<ToolAction> {
+        HashAlgorithm::iter()
+            .map(|algorithm| ToolAction {
+                id: algorithm.id(),
+                name: algorithm.name(),
+            })
+            .collect()
+    }
+
+    fn run_action(&mut self, id: &str) -> ToolResult<()> {
+        let algorithm = HashAlgorithm::iter()
+            .find(|algorithm| algorithm.id() == id)
+            .ok_or_else(|| ToolError::ProcessError(format!("unknown action {}", id)))?;
+        self.only = Some(*algorithm);
+        Ok(())
+    }
+
+    /// Every hash of the input, one `NAME: digest` per line, or just the
+    /// digest after an algorithm's action.
+    fn output(&self) -> Option<String> {
+        if self.input.is_empty() {
+            return None;
+        }
+        if let Some(algorithm) = self.only {
+            return Some(algorithm.hash(&self.input));
+        }
+        let lines: Vec<String> = HashAlgorithm::iter()
+            .map(|hasher| format!("{}: {}", hasher, hasher.hash(&self.input)))
+            .collect();
+        Some(lines.join("\n"))
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.input = input.to_string();
+        self.only = None;
+    }
+}
+
+impl ToolUI for HashView {
+    fn name(&self) -> &str {
+        "Hashing"
+    }
+
+    fn description(&self) -> &str {
+        "Hash a text with MD5, SHA1, SHA2, SHA3 or RIPEMD160."
+    }
+
+    fn icon(&self) -> &str {
+        "🔒"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Encoding
+    }
+
+    fn process_label(&self) -> &str {
+        "Hash"
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
             ui.heading("Hash Text");
             ui.separator();
@@ -57,7 +150,7 @@ impl View for HashView {
 impl HashView {
     fn hash_result_view(&mut self, ui: &mut egui::Ui, column_widths: &[f32]) {
         HashAlgorithm::iter().for_each(|hasher| {
-            ui.label(format!("{}:", hasher.to_string()));
+            ui.label(format!("{}:", hasher));
 
             // 第二列：哈希结果文本框
             ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
@@ -0,0 +1,181 @@
+use chrono::Utc;
+use egui::RichText;
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppStyle;
+use crate::model::{DateTimeConverter, Jwt};
+
+use crate::tools::{
+    from_json, process_result, to_json, ToolCategory, ToolLogic, ToolResult, ToolState, ToolUI,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JwtView {
+    pub input: String,
+    #[serde(skip)]
+    pub err_msg: String,
+    #[serde(skip)]
+    jwt: Option<Jwt>,
+}
+
+impl JwtView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn decode(&mut self) {
+        self.err_msg.clear();
+        self.jwt = None;
+        if self.input.trim().is_empty() {
+            return;
+        }
+
+        match Jwt::decode(&self.input) {
+            Ok(jwt) => self.jwt = Some(jwt),
+            Err(e) => self.err_msg = e,
+        }
+    }
+
+    fn claims(ui: &mut egui::Ui, jwt: &Jwt) {
+        let now = Utc::now();
+        if let Some(algorithm) = jwt.algorithm() {
+            ui.label("Algorithm:");
+            ui.monospace(algorithm);
+            ui.end_row();
+        }
+        for (label, time) in jwt.times() {
+            ui.label(format!("{}:", label));
+            ui.monospace(time.to_rfc3339());
+            ui.label(DateTimeConverter::relative(
+                time.fixed_offset(),
+                now.fixed_offset(),
+            ));
+            ui.end_row();
+        }
+    }
+
+    fn json_block(ui: &mut egui::Ui, title: &str, value: &serde_json::Value) {
+        ui.label(RichText::new(title).strong());
+        let mut text = serde_json::to_string_pretty(value).unwrap_or_default();
+        ui.add(
+            egui::TextEdit::multiline(&mut text)
+                .font(egui::TextStyle::Monospace)
+                .desired_width(f32::INFINITY)
+                .desired_rows(3),
+        );
+        ui.add_space(8.0);
+    }
+}
+
+impl ToolState for JwtView {
+    fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    fn save_state(&self) -> Option<String> {
+        to_json(self)
+    }
+
+    fn load_state(&mut self, state: &str) -> ToolResult<()> {
+        *self = from_json(state)?;
+        self.decode();
+        Ok(())
+    }
+}
+
+impl ToolLogic for JwtView {
+    fn process(&mut self) -> ToolResult<()> {
+        self.decode();
+        process_result(&self.err_msg)
+    }
+
+    fn validate(&self) -> bool {
+        !self.input.trim().is_empty()
+    }
+
+    /// The payload as pretty JSON.
+    fn output(&self) -> Option<String> {
+        let jwt = self.jwt.as_ref()?;
+        serde_json::to_string_pretty(&jwt.payload).ok()
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.input = input.trim().to_string();
+        self.decode();
+    }
+}
+
+impl ToolUI for JwtView {
+    fn name(&self) -> &str {
+        "JWT Decoder"
+    }
+
+    fn description(&self) -> &str {
+        "Decode a JSON Web Token and show its header, claims and expiry."
+    }
+
+    fn icon(&self) -> &str {
+        "🔑"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Encoding
+    }
+
+    fn process_label(&self) -> &str {
+        "Decode"
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.heading("JWT Decoder");
+            ui.separator();
+            ui.label(
+                RichText::new("Decode a JSON Web Token. The signature is shown but not verified.")
+                    .text_style(egui::TextStyle::Small),
+            );
+            ui.add_space(20.0);
+
+            let response = ui.add(
+                egui::TextEdit::multiline(&mut self.input)
+                    .font(egui::TextStyle::Monospace)
+                    .desired_width(f32::INFINITY)
+                    .desired_rows(4)
+                    .hint_text("eyJhbGciOi..."),
+            );
+            if response.changed() {
+                self.decode();
+            }
+
+            if !self.err_msg.is_empty() {
+                ui.colored_label(ui.visuals().error_fg_color, &self.err_msg);
+            }
+            let Some(jwt) = &self.jwt else {
+                return;
+            };
+
+            ui.add_space(10.0);
+            let style = AppStyle::current(ui.ctx());
+            match jwt.is_expired(Utc::now()) {
+                Some(true) => ui.colored_label(style.error, "Expired"),
+                Some(false) => ui.colored_label(style.success, "Not expired"),
+                None => ui.label("No expiry"),
+            };
+            egui::Grid::new("jwt claims")
+                .num_columns(3)
+                .spacing([10.0, 5.0])
+                .show(ui, |ui| {
+                    Self::claims(ui, jwt);
+                });
+            ui.add_space(10.0);
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                Self::json_block(ui, "Header", &jwt.header);
+                Self::json_block(ui, "Payload", &jwt.payload);
+                ui.label(RichText::new("Signature").strong());
+                ui.monospace(&jwt.signature);
+            });
+        });
+    }
+}
@@ -1,24 +1,18 @@
 pub mod calculator_view;
+pub mod command_palette;
+pub mod cron_view;
 pub mod datetime_view;
 pub mod differ_view;
 pub mod fish_view;
 pub mod formatter_view;
 pub mod game_of_life;
 pub mod hash_view;
+pub mod jwt_view;
 pub mod number_view;
-
-pub trait View {
-    fn render(&mut self, ctx: &egui::Context, ui: &mut egui::Ui);
-}
-
-#[derive(Default, Clone, PartialEq, Eq, Hash, Debug)]
-pub struct DefaultView;
-
-impl View for DefaultView {
-    fn render(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
-        ui.label("is default view");
-    }
-}
+pub mod patch_view;
+pub mod pipeline_view;
+pub mod settings_view;
+pub mod syntax;
 
 pub trait WindowView {
     /// Is the demo enabled for this integration?
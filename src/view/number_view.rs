@@ -1,16 +1,40 @@
 // number base converter
 
 use egui::RichText;
+use num_bigint::BigInt;
+use num_traits::Signed;
+use serde::{Deserialize, Serialize};
 
-use crate::model::NumberBaseConverter;
+use crate::model::{
+    format_hex_bytes, parse_hex_bytes, BitField, BitWidth, Endianness, FloatFormat,
+    NumberBaseConverter, MAX_INSPECTED_BITS,
+};
 
-use super::View;
+use crate::tools::{
+    from_json, process_result, to_json, ToolCategory, ToolLogic, ToolResult, ToolState, ToolUI,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct NumberBaseConverterView {
     pub input: String,
     pub base: NumberBaseConverter,
-    pub num: i64,
+    pub width: BitWidth,
+    pub custom_radix: u32,
+    #[serde(skip)]
+    pub num: BigInt,
+    #[serde(skip)]
+    pub err_msg: String,
+    pub bit_fields: String,
+    pub hex_bytes: String,
+    pub float_input: String,
+    pub endianness: Endianness,
+}
+
+impl Default for NumberBaseConverterView {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl NumberBaseConverterView {
@@ -18,30 +42,363 @@ impl NumberBaseConverterView {
         Self {
             input: String::from("36"),
             base: NumberBaseConverter::Decimal,
-            num: 36,
+            width: BitWidth::Unbounded,
+            custom_radix: 36,
+            num: BigInt::from(36),
+            err_msg: String::new(),
+            bit_fields: String::from("flags[3:0]=mode\nflags[7]=enable"),
+            hex_bytes: String::from("40 49 0f db"),
+            float_input: String::from("3.14159"),
+            endianness: Endianness::Big,
         }
     }
 
-    fn convert_list(&mut self, ui: &mut egui::Ui) {
-        let decimal = if self.input.is_empty() {
-            0
-        } else {
-            self.base
-                .to_decimal(self.input.as_str())
-                .unwrap_or_default()
-        };
+    fn parse(&mut self) {
+        self.err_msg.clear();
+        if self.input.trim().is_empty() {
+            self.num = BigInt::default();
+            return;
+        }
+
+        match self.base.to_decimal(&self.input) {
+            Ok(num) => self.num = num,
+            Err(e) => {
+                // the old value's conversions would pass for this input's
+                self.num = BigInt::default();
+                self.err_msg = e;
+            }
+        }
+    }
+
+    fn converters(&self) -> Vec<NumberBaseConverter> {
+        NumberBaseConverter::iter()
+            .copied()
+            .chain([NumberBaseConverter::Radix(self.custom_radix)])
+            .collect()
+    }
 
-        NumberBaseConverter::iter().for_each(|converter| {
+    fn convert_list(&mut self, ui: &mut egui::Ui) {
+        if !self.err_msg.is_empty() {
+            return;
+        }
+        self.converters().iter().for_each(|converter| {
             ui.label(RichText::new(converter.to_string()));
-            let convert_str = converter.convert(decimal);
-            ui.text_edit_singleline(&mut convert_str.unwrap());
+            match converter.convert(&self.num, self.width) {
+                Ok(mut convert_str) => {
+                    ui.text_edit_singleline(&mut convert_str);
+                }
+                Err(e) => {
+                    ui.colored_label(ui.visuals().error_fg_color, e);
+                }
+            }
+            ui.end_row();
+        });
+
+        if self.width.bits().is_some() {
+            ui.label("Unsigned(10)");
+            if let Ok(mut unsigned) = self.width.unsigned(&self.num).map(|n| n.to_string()) {
+                ui.text_edit_singleline(&mut unsigned);
+            }
             ui.end_row();
+        }
+    }
+}
+
+impl NumberBaseConverterView {
+    fn bit_inspector(&mut self, ui: &mut egui::Ui) {
+        let mut pattern = match self.width.unsigned(&self.num) {
+            Ok(pattern) if pattern.is_negative() => {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    "Choose a bit width to inspect negative numbers",
+                );
+                return;
+            }
+            Ok(pattern) => pattern,
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+                return;
+            }
+        };
+
+        let bits = self
+            .width
+            .bits()
+            .unwrap_or_else(|| (pattern.bits() as u32).max(8).div_ceil(8) * 8)
+            .min(MAX_INSPECTED_BITS);
+
+        // 16 bits per row, most significant first
+        let mut toggled = None;
+        for row_start in (0..bits).step_by(16).rev() {
+            ui.horizontal(|ui| {
+                let row_end = (row_start + 16).min(bits);
+                ui.monospace(format!("{:>3}", row_end - 1));
+                for bit in (row_start..row_end).rev() {
+                    let set = pattern.bit(bit as u64);
+                    let button =
+                        egui::Button::new(RichText::new(if set { "1" } else { "0" }).monospace())
+                            .selected(set)
+                            .min_size(egui::vec2(16.0, 0.0));
+                    if ui
+                        .add(button)
+                        .on_hover_text(format!("bit {}", bit))
+                        .clicked()
+                    {
+                        toggled = Some(bit);
+                    }
+                    if bit % 8 == 0 {
+                        ui.add_space(10.0);
+                    } else if bit % 4 == 0 {
+                        ui.add_space(4.0);
+                    }
+                }
+                ui.monospace(format!("{}", row_start));
+            });
+        }
+
+        if let Some(bit) = toggled {
+            pattern.set_bit(bit as u64, !pattern.bit(bit as u64));
+            self.num = pattern.clone();
+            self.err_msg.clear();
+            if let Ok(input) = self.base.convert(&self.num, self.width) {
+                self.input = input;
+            }
+        }
+
+        ui.add_space(10.0);
+        ui.label("Bit fields:");
+        ui.add(
+            egui::TextEdit::multiline(&mut self.bit_fields)
+                .font(egui::TextStyle::Monospace)
+                .desired_rows(3)
+                .hint_text("flags[3:0]=mode"),
+        );
+
+        match BitField::parse_list(&self.bit_fields) {
+            Ok(fields) => {
+                egui::Grid::new("bit fields")
+                    .num_columns(4)
+                    .spacing([10.0, 5.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for field in fields {
+                            let value = field.extract(&pattern);
+                            ui.label(&field.name);
+                            ui.monospace(format!("[{}:{}]", field.high, field.low));
+                            ui.monospace(value.to_string());
+                            ui.monospace(format!(
+                                "0b{:0>width$}",
+                                value.to_str_radix(2),
+                                width = field.width() as usize
+                            ));
+                            ui.end_row();
+                        }
+                    });
+            }
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+        }
+    }
+}
+
+impl NumberBaseConverterView {
+    fn byte_decoder(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.radio_value(
+                &mut self.endianness,
+                Endianness::Big,
+                Endianness::Big.to_string(),
+            );
+            ui.radio_value(
+                &mut self.endianness,
+                Endianness::Little,
+                Endianness::Little.to_string(),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Hex bytes:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.hex_bytes)
+                    .font(egui::TextStyle::Monospace)
+                    .hint_text("de ad be ef"),
+            );
         });
+
+        let bytes = match parse_hex_bytes(&self.hex_bytes) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+                Vec::new()
+            }
+        };
+
+        egui::Grid::new("float decode")
+            .num_columns(5)
+            .spacing([10.0, 5.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Type", "Value", "Sign", "Exponent", "Mantissa"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for format in FloatFormat::iter() {
+                    let Some(parts) = format.decode(&bytes, self.endianness) else {
+                        continue;
+                    };
+                    ui.label(format.to_string());
+                    ui.monospace(format!("{}  ({})", parts.value, parts.class(*format)));
+                    ui.monospace(if parts.negative { "1 (-)" } else { "0 (+)" });
+                    ui.monospace(format!(
+                        "{} (2^{})",
+                        parts.exponent,
+                        parts.unbiased_exponent(*format)
+                    ));
+                    ui.monospace(format!(
+                        "0x{:0width$x}",
+                        parts.mantissa,
+                        width = format.mantissa_bits().div_ceil(4) as usize
+                    ));
+                    ui.end_row();
+                }
+            });
+
+        ui.add_space(8.0);
+        egui::Grid::new("integer decode")
+            .num_columns(3)
+            .spacing([10.0, 5.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Width", "Unsigned", "Signed"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for width in BitWidth::iter() {
+                    let Some((unsigned, signed)) = width.from_bytes(&bytes, self.endianness) else {
+                        continue;
+                    };
+                    ui.label(width.to_string());
+                    ui.monospace(unsigned.to_string());
+                    ui.monospace(signed.to_string());
+                    ui.end_row();
+                }
+            });
+
+        ui.add_space(15.0);
+        ui.label(RichText::new("Encode").strong());
+        egui::Grid::new("byte encode")
+            .num_columns(2)
+            .spacing([10.0, 5.0])
+            .show(ui, |ui| {
+                ui.label("Float value:");
+                ui.add(egui::TextEdit::singleline(&mut self.float_input).hint_text("3.14"));
+                ui.end_row();
+
+                match self.float_input.trim().parse::<f64>() {
+                    Ok(value) => {
+                        for format in FloatFormat::iter() {
+                            ui.label(format.to_string());
+                            let mut encoded =
+                                format_hex_bytes(&format.encode(value, self.endianness));
+                            ui.text_edit_singleline(&mut encoded);
+                            ui.end_row();
+                        }
+                    }
+                    Err(_) => {
+                        ui.label("");
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            "Please enter a valid number",
+                        );
+                        ui.end_row();
+                    }
+                }
+
+                ui.label(format!("Integer ({})", self.width));
+                match self.width.to_bytes(&self.num, self.endianness) {
+                    Ok(bytes) => {
+                        let mut encoded = format_hex_bytes(&bytes);
+                        ui.text_edit_singleline(&mut encoded);
+                    }
+                    Err(e) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                    }
+                }
+                ui.end_row();
+            });
     }
 }
 
-impl View for NumberBaseConverterView {
-    fn render(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+impl ToolState for NumberBaseConverterView {
+    fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    fn save_state(&self) -> Option<String> {
+        to_json(self)
+    }
+
+    fn load_state(&mut self, state: &str) -> ToolResult<()> {
+        *self = from_json(state)?;
+        self.parse();
+        Ok(())
+    }
+}
+
+impl ToolLogic for NumberBaseConverterView {
+    fn process(&mut self) -> ToolResult<()> {
+        self.parse();
+        process_result(&self.err_msg)
+    }
+
+    fn validate(&self) -> bool {
+        !self.input.trim().is_empty()
+    }
+
+    /// The value in decimal.
+    fn output(&self) -> Option<String> {
+        Some(self.num.to_string()).filter(|_| self.err_msg.is_empty())
+    }
+
+    /// Picks decimal or hexadecimal from the text, `0x` prefixes are dropped.
+    fn set_input(&mut self, input: &str) {
+        let input = input.trim();
+        let hex = input
+            .strip_prefix("0x")
+            .or_else(|| input.strip_prefix("0X"));
+        self.input = hex.unwrap_or(input).to_string();
+        if hex.is_none() && input.chars().all(|c| c.is_ascii_digit() || c == '-') {
+            self.base = NumberBaseConverter::Decimal;
+        } else if self.input.chars().all(|c| c.is_ascii_hexdigit()) {
+            self.base = NumberBaseConverter::Hexadecimal;
+        }
+        self.parse();
+    }
+}
+
+impl ToolUI for NumberBaseConverterView {
+    fn name(&self) -> &str {
+        "Number Base Converter"
+    }
+
+    fn description(&self) -> &str {
+        "Convert numbers between bases and inspect their bits and bytes."
+    }
+
+    fn icon(&self) -> &str {
+        "🔢"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Conversion
+    }
+
+    fn process_label(&self) -> &str {
+        "Convert"
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
         ui.vertical(|ui|{
             ui.heading("Number base Converter");
             ui.separator();
@@ -52,26 +409,54 @@ impl View for NumberBaseConverterView {
             ui.horizontal(|ui|{
                 // text edit input number
                 let text_edit = egui::TextEdit::singleline(&mut self.input)
-                .hint_text("PInput number here...");
+                .hint_text("Input number here, e.g. 0xff or -42...");
 
-                let _response = ui.add(text_edit);
+                let response = ui.add(text_edit);
                 ui.add_space(15.0);
                     // 添加格式选择下拉框
+                    let base = self.base;
+                    let converters = self.converters();
                     egui::ComboBox::from_label("")
-                    .selected_text(&self.base.to_string())
+                    .selected_text(self.base.to_string())
                     .show_ui(ui, |ui| {
-                        NumberBaseConverter::iter()
+                        converters.iter()
                         .for_each(|converter|{
                             ui.selectable_value(&mut self.base, *converter, converter.to_string());
                         });
                     });
+
+                    let radix = ui.add(
+                        egui::DragValue::new(&mut self.custom_radix)
+                            .range(2..=36)
+                            .prefix("radix "),
+                    );
+                    if radix.changed() {
+                        if let NumberBaseConverter::Radix(_) = self.base {
+                            self.base = NumberBaseConverter::Radix(self.custom_radix);
+                        }
+                    }
+
+                    egui::ComboBox::from_id_salt("bit width")
+                    .selected_text(self.width.to_string())
+                    .show_ui(ui, |ui| {
+                        BitWidth::iter().for_each(|width| {
+                            ui.selectable_value(&mut self.width, *width, width.to_string());
+                        });
+                    });
+
+                    if response.changed() || base != self.base {
+                        self.parse();
+                    }
                 });
+
+                if !self.err_msg.is_empty() {
+                    ui.colored_label(ui.visuals().error_fg_color, &self.err_msg);
+                }
+
                  ui.add_space(15.0);
-                // formater list
-                let _column_widths = [60.0, ui.available_width() - 60.0, 60.0]; // 定义三列宽度
                 // 计算可用宽度（留出20px边距）
                 let available_width = ui.available_width() - 100.0;
-                // println!("available_width:{}",available_width);
+                egui::ScrollArea::vertical().show(ui, |ui| {
                 egui::Grid::new("formatter list")
                 .num_columns(2)
                 .min_col_width(40.0) // 设置列最小宽度
@@ -80,6 +465,20 @@ impl View for NumberBaseConverterView {
                 .show(ui, |ui| {
                 self.convert_list(ui);
                 });
+
+                ui.add_space(15.0);
+                egui::CollapsingHeader::new(RichText::new("Bit inspector").strong())
+                .default_open(true)
+                .show(ui, |ui| {
+                    self.bit_inspector(ui);
+                });
+
+                egui::CollapsingHeader::new(RichText::new("Bytes & floats").strong())
+                .default_open(false)
+                .show(ui, |ui| {
+                    self.byte_decoder(ui);
+                });
+                });
         });
     }
 }
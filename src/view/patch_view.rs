@@ -0,0 +1,224 @@
+use egui::RichText;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{DiffLine, DiffOptions, Patch, PatchResult, TextDifference};
+
+use super::differ_view::DifferenceView;
+use crate::tools::{
+    from_json, process_result, to_json, ToolCategory, ToolLogic, ToolResult, ToolState, ToolUI,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PatchView {
+    pub original: String,
+    pub patch_input: String,
+    pub reverse: bool,
+    pub fuzz: usize,
+    pub side_by_side: bool,
+    #[serde(skip)]
+    pub err_msg: String,
+    #[serde(skip)]
+    patch: Option<Patch>,
+    #[serde(skip)]
+    result: Option<PatchResult>,
+    #[serde(skip)]
+    lines: Vec<DiffLine>,
+}
+
+impl Default for PatchView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PatchView {
+    pub fn new() -> Self {
+        Self {
+            original: String::new(),
+            patch_input: String::new(),
+            reverse: false,
+            fuzz: 2,
+            side_by_side: false,
+            err_msg: String::new(),
+            patch: None,
+            result: None,
+            lines: Vec::new(),
+        }
+    }
+
+    fn apply(&mut self) {
+        self.err_msg.clear();
+        self.patch = None;
+        self.result = None;
+        self.lines.clear();
+        if self.patch_input.trim().is_empty() {
+            return;
+        }
+
+        match Patch::parse(&self.patch_input) {
+            Ok(patch) => {
+                let patch = if self.reverse { patch.reverse() } else { patch };
+                let result = patch.apply(&self.original, self.fuzz);
+                self.lines =
+                    TextDifference::lines(&self.original, &result.text, &DiffOptions::default());
+                self.patch = Some(patch);
+                self.result = Some(result);
+            }
+            Err(e) => self.err_msg = e,
+        }
+    }
+
+    fn summary(ui: &mut egui::Ui, patch: &Patch, result: &PatchResult) {
+        for applied in &result.applied {
+            let mut text = format!("Hunk #{} applied", applied.index + 1);
+            if applied.offset != 0 {
+                text.push_str(&format!(" at offset {}", applied.offset));
+            }
+            if applied.fuzz != 0 {
+                text.push_str(&format!(" with fuzz {}", applied.fuzz));
+            }
+            ui.label(RichText::new(text).text_style(egui::TextStyle::Small));
+        }
+
+        for &index in &result.rejected {
+            let hunk = &patch.hunks[index];
+            egui::CollapsingHeader::new(
+                RichText::new(format!("Hunk #{} rejected {}", index + 1, hunk.header()))
+                    .color(ui.visuals().error_fg_color),
+            )
+            .id_salt(("rejected hunk", index))
+            .show(ui, |ui| {
+                DifferenceView::show_lines(ui, &hunk.lines, false, None);
+            });
+        }
+    }
+}
+
+impl ToolState for PatchView {
+    fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    fn save_state(&self) -> Option<String> {
+        to_json(self)
+    }
+
+    fn load_state(&mut self, state: &str) -> ToolResult<()> {
+        *self = from_json(state)?;
+        self.apply();
+        Ok(())
+    }
+}
+
+impl ToolLogic for PatchView {
+    fn process(&mut self) -> ToolResult<()> {
+        self.apply();
+        process_result(&self.err_msg)
+    }
+
+    fn validate(&self) -> bool {
+        !self.patch_input.trim().is_empty()
+    }
+
+    fn output(&self) -> Option<String> {
+        self.result.as_ref().map(|result| result.text.clone())
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.patch_input = input.to_string();
+        self.apply();
+    }
+}
+
+impl ToolUI for PatchView {
+    fn name(&self) -> &str {
+        "Patch"
+    }
+
+    fn description(&self) -> &str {
+        "Apply or reverse a unified diff (diff -u, git diff) to a text."
+    }
+
+    fn icon(&self) -> &str {
+        "📝"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Text
+    }
+
+    fn process_label(&self) -> &str {
+        "Apply"
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.heading("Patch");
+            ui.separator();
+            ui.label(
+                RichText::new("Apply or reverse a unified diff (diff -u, git diff) to a text.")
+                    .text_style(egui::TextStyle::Small),
+            );
+            ui.add_space(20.0);
+
+            let mut changed = false;
+            ui.horizontal(|ui| {
+                changed |= ui.checkbox(&mut self.reverse, "Reverse").changed();
+                ui.label("Fuzz:");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut self.fuzz).range(0..=3))
+                    .on_hover_text("Context lines that may be ignored at each end of a hunk")
+                    .changed();
+                ui.checkbox(&mut self.side_by_side, "Side by side");
+            });
+
+            ui.horizontal(|ui| {
+                let max_width = ui.available_width();
+                changed |= ui
+                    .add(
+                        egui::TextEdit::multiline(&mut self.original)
+                            .font(egui::TextStyle::Monospace)
+                            .hint_text("Original text")
+                            .desired_rows(12)
+                            .desired_width(max_width * 0.46),
+                    )
+                    .changed();
+                changed |= ui
+                    .add(
+                        egui::TextEdit::multiline(&mut self.patch_input)
+                            .font(egui::TextStyle::Monospace)
+                            .hint_text("--- a/file\n+++ b/file\n@@ -1,3 +1,3 @@")
+                            .desired_rows(12)
+                            .desired_width(max_width * 0.46),
+                    )
+                    .changed();
+            });
+            if changed {
+                self.apply();
+            }
+
+            ui.separator();
+            if !self.err_msg.is_empty() {
+                ui.colored_label(ui.visuals().error_fg_color, &self.err_msg);
+            }
+
+            let (Some(patch), Some(result)) = (&self.patch, &self.result) else {
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("Result :").strong());
+                if ui.button("Copy result").clicked() {
+                    ui.ctx().copy_text(result.text.clone());
+                }
+            });
+            egui::ScrollArea::vertical()
+                .auto_shrink(false)
+                .show(ui, |ui| {
+                    Self::summary(ui, patch, result);
+                    ui.add_space(10.0);
+                    DifferenceView::show_lines(ui, &self.lines, self.side_by_side, None);
+                });
+        });
+    }
+}
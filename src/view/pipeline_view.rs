@@ -0,0 +1,374 @@
+use egui::RichText;
+use serde::{Deserialize, Serialize};
+
+use crate::tools::{
+    from_json, process_result, to_json, Pipeline, PipelineStep, StepResult, ToolCategory,
+    ToolLogic, ToolRegistry, ToolResult, ToolState, ToolUI,
+};
+
+/// Registry id of this tool, which cannot be a step of itself.
+const PIPELINE_ID: &str = "pipeline";
+
+enum StepEdit {
+    Up(usize),
+    Down(usize),
+    Remove(usize),
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipelineView {
+    pub pipeline: Pipeline,
+    pub input: String,
+    #[serde(skip)]
+    results: Vec<StepResult>,
+    #[serde(skip)]
+    err_msg: String,
+    /// Content of the pipelines file, read on first use.
+    #[serde(skip)]
+    saved: Option<Vec<Pipeline>>,
+    /// Built on first use, the registry creates a pipeline view itself.
+    #[serde(skip)]
+    registry: Option<ToolRegistry>,
+}
+
+impl PipelineView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn run(&mut self) {
+        self.err_msg.clear();
+        let registry = self.registry.get_or_insert_with(ToolRegistry::builtin);
+        self.results = self.pipeline.run(registry, &self.input);
+        // a run stops at the first failing step
+        if let Some(Err(e)) = self.results.last().map(|result| &result.output) {
+            self.err_msg = format!("Step {}: {}", self.results.len(), e);
+        }
+    }
+
+    fn saved(&mut self) -> &mut Vec<Pipeline> {
+        self.saved.get_or_insert_with(|| {
+            Pipeline::file()
+                .map(|path| Pipeline::load_all(&path).unwrap_or_default())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Writes the pipeline to the pipelines file, replacing one of the same
+    /// name.
+    fn save(&mut self) {
+        let name = self.pipeline.name.trim().to_string();
+        if name.is_empty() {
+            self.err_msg = "Give the pipeline a name to save it".to_string();
+            return;
+        }
+        self.pipeline.name = name;
+        let pipeline = self.pipeline.clone();
+        let saved = self.saved();
+        match saved.iter_mut().find(|saved| saved.name == pipeline.name) {
+            Some(existing) => *existing = pipeline,
+            None => saved.push(pipeline),
+        }
+        self.write_saved();
+    }
+
+    fn delete(&mut self, name: &str) {
+        self.saved().retain(|saved| saved.name != name);
+        self.write_saved();
+    }
+
+    fn write_saved(&mut self) {
+        let Some(path) = Pipeline::file() else {
+            self.err_msg = "No place to save pipelines on this system".to_string();
+            return;
+        };
+        let saved = self.saved.clone().unwrap_or_default();
+        if let Err(e) = Pipeline::save_all(&path, &saved) {
+            self.err_msg = e;
+        }
+    }
+
+    fn render_saved(&mut self, ui: &mut egui::Ui) {
+        let mut load = None;
+        let mut delete = None;
+        ui.menu_button("📂 Saved", |ui| {
+            let saved = self.saved();
+            if saved.is_empty() {
+                ui.weak("No saved pipelines");
+            }
+            for pipeline in saved.iter() {
+                ui.horizontal(|ui| {
+                    if ui.button(&pipeline.name).clicked() {
+                        load = Some(pipeline.clone());
+                        ui.close_menu();
+                    }
+                    if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                        delete = Some(pipeline.name.clone());
+                    }
+                });
+            }
+        });
+        if let Some(pipeline) = load {
+            self.pipeline = pipeline;
+            self.run();
+        }
+        if let Some(name) = delete {
+            self.delete(&name);
+        }
+    }
+
+    /// Returns whether a step changed.
+    fn render_steps(&mut self, ui: &mut egui::Ui) -> bool {
+        let registry = self.registry.get_or_insert_with(ToolRegistry::builtin);
+        let count = self.pipeline.steps.len();
+        let mut changed = false;
+        let mut edit = None;
+
+        for (index, step) in self.pipeline.steps.iter_mut().enumerate() {
+            let Some(tool) = registry.create(&step.tool) else {
+                continue;
+            };
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}.", index + 1));
+                    egui::ComboBox::from_id_salt(("pipeline tool", index))
+                        .selected_text(format!("{} {}", tool.icon(), tool.name()))
+                        .show_ui(ui, |ui| {
+                            for entry in registry.iter().filter(|e| e.id != PIPELINE_ID) {
+                                let label = format!("{} {}", entry.icon, entry.name);
+                                if ui.selectable_label(step.tool == entry.id, label).clicked()
+                                    && step.tool != entry.id
+                                {
+                                    *step = PipelineStep::new(entry.id, None);
+                                    changed = true;
+                                }
+                            }
+                        });
+
+                    let actions = tool.actions();
+                    let selected = match &step.action {
+                        Some(id) => actions
+                            .iter()
+                            .find(|action| action.id == id)
+                            .map_or(id.as_str(), |action| action.name),
+                        None => tool.process_label(),
+                    };
+                    egui::ComboBox::from_id_salt(("pipeline action", index))
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            if ui
+                                .selectable_label(step.action.is_none(), tool.process_label())
+                                .clicked()
+                            {
+                                step.action = None;
+                                changed = true;
+                            }
+                            for action in &actions {
+                                let is_selected = step.action.as_deref() == Some(action.id);
+                                if ui.selectable_label(is_selected, action.name).clicked() {
+                                    step.action = Some(action.id.to_string());
+                                    changed = true;
+                                }
+                            }
+                        });
+
+                    if ui
+                        .selectable_label(step.state.is_some(), "⚙")
+                        .on_hover_text("Options, as the tool's saved state in JSON")
+                        .clicked()
+                    {
+                        step.state = match step.state {
+                            Some(_) => None,
+                            None => tool.save_state(),
+                        };
+                        changed = true;
+                    }
+                    if ui.add_enabled(index > 0, egui::Button::new("⏶")).clicked() {
+                        edit = Some(StepEdit::Up(index));
+                    }
+                    if ui
+                        .add_enabled(index + 1 < count, egui::Button::new("⏷"))
+                        .clicked()
+                    {
+                        edit = Some(StepEdit::Down(index));
+                    }
+                    if ui.button("✖").on_hover_text("Remove step").clicked() {
+                        edit = Some(StepEdit::Remove(index));
+                    }
+                });
+
+                if let Some(state) = &mut step.state {
+                    changed |= ui
+                        .add(
+                            egui::TextEdit::multiline(state)
+                                .font(egui::TextStyle::Monospace)
+                                .desired_width(f32::INFINITY)
+                                .desired_rows(2),
+                        )
+                        .changed();
+                }
+
+                match self.results.get(index).map(|result| &result.output) {
+                    Some(Ok(output)) => {
+                        let preview: String = output
+                            .lines()
+                            .next()
+                            .unwrap_or_default()
+                            .chars()
+                            .take(60)
+                            .collect();
+                        egui::CollapsingHeader::new(RichText::new(preview).monospace().weak())
+                            .id_salt(("pipeline output", index))
+                            .show(ui, |ui| {
+                                ui.add(
+                                    egui::TextEdit::multiline(&mut output.as_str())
+                                        .font(egui::TextStyle::Monospace)
+                                        .desired_width(f32::INFINITY)
+                                        .desired_rows(3),
+                                );
+                            });
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                    }
+                    None => {}
+                }
+            });
+        }
+
+        match edit {
+            Some(StepEdit::Up(index)) => self.pipeline.steps.swap(index, index - 1),
+            Some(StepEdit::Down(index)) => self.pipeline.steps.swap(index, index + 1),
+            Some(StepEdit::Remove(index)) => {
+                self.pipeline.steps.remove(index);
+            }
+            None => {}
+        }
+        changed || edit.is_some()
+    }
+}
+
+impl ToolState for PipelineView {
+    fn clear(&mut self) {
+        self.pipeline = Pipeline::default();
+        self.input.clear();
+        self.results.clear();
+        self.err_msg.clear();
+    }
+
+    fn save_state(&self) -> Option<String> {
+        to_json(self)
+    }
+
+    fn load_state(&mut self, state: &str) -> ToolResult<()> {
+        let loaded: PipelineView = from_json(state)?;
+        self.pipeline = loaded.pipeline;
+        self.input = loaded.input;
+        self.results.clear();
+        if self.validate() {
+            self.run();
+        }
+        Ok(())
+    }
+}
+
+impl ToolLogic for PipelineView {
+    fn process(&mut self) -> ToolResult<()> {
+        self.run();
+        process_result(&self.err_msg)
+    }
+
+    fn validate(&self) -> bool {
+        !self.pipeline.steps.is_empty()
+    }
+
+    /// Output of the last step, when every step succeeded.
+    fn output(&self) -> Option<String> {
+        if self.results.len() < self.pipeline.steps.len() {
+            return None;
+        }
+        self.results.last()?.output.clone().ok()
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.input = input.to_string();
+    }
+}
+
+impl ToolUI for PipelineView {
+    fn name(&self) -> &str {
+        "Pipeline"
+    }
+
+    fn description(&self) -> &str {
+        "Chain tools, each step gets the output of the one before it."
+    }
+
+    fn icon(&self) -> &str {
+        "⛓"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Automation
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.heading("Pipeline");
+            ui.separator();
+            ui.label(
+                RichText::new(
+                    "Chain tools: the input goes to the first step and every step's output to the next one. Saved pipelines also run headless with --pipeline <name>.",
+                )
+                .text_style(egui::TextStyle::Small),
+            );
+            ui.add_space(15.0);
+
+            ui.horizontal(|ui| {
+                ui.label("Name:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.pipeline.name)
+                        .desired_width(160.0)
+                        .hint_text("decode and hash"),
+                );
+                if ui.button("💾 Save").clicked() {
+                    self.save();
+                }
+                self.render_saved(ui);
+            });
+            ui.add_space(10.0);
+
+            ui.label("Input:");
+            let mut changed = ui
+                .add(
+                    egui::TextEdit::multiline(&mut self.input)
+                        .font(egui::TextStyle::Monospace)
+                        .desired_width(f32::INFINITY)
+                        .desired_rows(3),
+                )
+                .changed();
+            ui.add_space(10.0);
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                changed |= self.render_steps(ui);
+                ui.horizontal(|ui| {
+                    if ui.button("➕ Add step").clicked() {
+                        self.pipeline.steps.push(PipelineStep::new("hash", None));
+                        changed = true;
+                    }
+                    if ui.button("▶ Run").clicked() {
+                        changed = true;
+                    }
+                });
+                if !self.err_msg.is_empty() {
+                    ui.colored_label(ui.visuals().error_fg_color, &self.err_msg);
+                }
+            });
+
+            if changed {
+                self.run();
+            }
+        });
+    }
+}
@@ -0,0 +1,170 @@
+use std::path::Path;
+
+use egui::RichText;
+
+use crate::config::{fonts, Settings};
+
+/// Window editing a copy of the settings, which only take effect when
+/// applied.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SettingsView {
+    pub open: bool,
+    draft: Settings,
+}
+
+impl SettingsView {
+    /// Opens the window on the current settings.
+    pub fn open(&mut self, settings: &Settings) {
+        self.open = true;
+        self.draft = settings.clone();
+    }
+
+    /// Shows the window when it is open and returns the settings to apply.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<Settings> {
+        if !self.open {
+            return None;
+        }
+
+        let mut open = true;
+        let mut apply = false;
+        egui::Window::new("Settings")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .show(ctx, |ui| {
+                egui::Grid::new("settings")
+                    .num_columns(2)
+                    .spacing([12.0, 8.0])
+                    .show(ui, |ui| self.render_grid(ui));
+
+                if let Some(path) = Settings::file() {
+                    ui.add_space(6.0);
+                    ui.label(
+                        RichText::new(format!("Saved to {}", path.display()))
+                            .text_style(egui::TextStyle::Small)
+                            .weak(),
+                    );
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    apply = ui.button("✔ Apply").clicked();
+                    if ui.button("↺ Defaults").clicked() {
+                        self.draft = Settings::default();
+                    }
+                    if ui.button("Close").clicked() {
+                        self.open = false;
+                    }
+                });
+            });
+
+        if !open || ctx.input(|input| input.key_pressed(egui::Key::Escape)) {
+            self.open = false;
+        }
+        apply.then(|| self.draft.clone().clamped())
+    }
+
+    fn render_grid(&mut self, ui: &mut egui::Ui) {
+        let draft = &mut self.draft;
+
+        ui.label("UI scale:");
+        ui.add(
+            egui::Slider::new(&mut draft.ui_scale, Settings::UI_SCALE)
+                .step_by(0.05)
+                .custom_formatter(|scale, _| format!("{:.0}%", scale * 100.0)),
+        );
+        ui.end_row();
+
+        ui.label("Font:");
+        font_picker(ui, "body font", &mut draft.body_font, false);
+        ui.end_row();
+
+        ui.label("Font size:");
+        ui.add(egui::Slider::new(&mut draft.body_size, Settings::FONT_SIZE).step_by(1.0));
+        ui.end_row();
+
+        ui.label("Monospace font:");
+        font_picker(ui, "monospace font", &mut draft.monospace_font, false);
+        ui.end_row();
+
+        ui.label("Monospace size:");
+        ui.add(egui::Slider::new(&mut draft.monospace_size, Settings::FONT_SIZE).step_by(1.0));
+        ui.end_row();
+
+        ui.label("CJK fallback:")
+            .on_hover_text("Used for characters the fonts above lack");
+        font_picker(ui, "cjk fallback", &mut draft.cjk_fallback, true);
+        ui.end_row();
+
+        ui.label("Window size:")
+            .on_hover_text("Size of the window when the app starts");
+        ui.horizontal(|ui| {
+            let [width, height] = &mut draft.window_size;
+            let [min_width, min_height] = Settings::MIN_WINDOW_SIZE;
+            ui.add(
+                egui::DragValue::new(width)
+                    .range(min_width..=4096.0)
+                    .suffix(" px"),
+            );
+            ui.label("×");
+            ui.add(
+                egui::DragValue::new(height)
+                    .range(min_height..=4096.0)
+                    .suffix(" px"),
+            );
+            if ui.button("Current").clicked() {
+                if let Some(rect) = ui.ctx().input(|input| input.viewport().inner_rect) {
+                    // the rect is in UI points, the window size leaves out the UI scale
+                    let size = rect.size() * ui.ctx().zoom_factor();
+                    *width = size.x.round();
+                    *height = size.y.round();
+                }
+            }
+        });
+        ui.end_row();
+    }
+}
+
+/// Combo box of the bundled fonts and a file picker for any other font.
+fn font_picker(ui: &mut egui::Ui, id: &str, font: &mut String, cjk: bool) {
+    let bundled: Vec<&str> = if cjk {
+        fonts::CJK_FONTS.to_vec()
+    } else {
+        fonts::BUNDLED_FONTS.iter().map(|(name, _)| *name).collect()
+    };
+    let selected = if font.is_empty() {
+        "None".to_string()
+    } else if bundled.contains(&font.as_str()) {
+        font.clone()
+    } else {
+        // a font file, by its file name
+        Path::new(font.as_str())
+            .file_name()
+            .map_or(font.clone(), |name| name.to_string_lossy().to_string())
+    };
+
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(selected)
+        .width(200.0)
+        .show_ui(ui, |ui| {
+            if cjk && ui.selectable_label(font.is_empty(), "None").clicked() {
+                font.clear();
+            }
+            for name in bundled {
+                if ui.selectable_label(font == name, name).clicked() {
+                    *font = name.to_string();
+                }
+            }
+            if ui.button("Font file…").clicked() {
+                ui.close_menu();
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Fonts", &["ttf", "otf", "ttc"])
+                    .pick_file()
+                {
+                    *font = path.display().to_string();
+                }
+            }
+        })
+        .response
+        .on_hover_text(font.as_str());
+}
@@ -0,0 +1,120 @@
+// syntax highlighting with the syntect theme of the current AppStyle
+
+use egui::{
+    text::{LayoutJob, LayoutSection},
+    Color32, FontId, TextFormat,
+};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{FontStyle, ThemeSet},
+    parsing::SyntaxSet,
+    util::LinesWithEndings,
+};
+
+use crate::config::AppStyle;
+
+struct Highlighter {
+    syntaxes: SyntaxSet,
+    themes: ThemeSet,
+}
+
+impl Default for Highlighter {
+    fn default() -> Self {
+        Self {
+            syntaxes: SyntaxSet::load_defaults_newlines(),
+            themes: ThemeSet::load_defaults(),
+        }
+    }
+}
+
+type HighlightKey<'a> = (&'a FontId, &'a str, bool, &'a str, &'a str);
+
+impl egui::cache::ComputerMut<HighlightKey<'_>, LayoutJob> for Highlighter {
+    fn compute(&mut self, (font_id, theme, dark, code, language): HighlightKey<'_>) -> LayoutJob {
+        self.highlight(font_id, theme, dark, code, language)
+            .unwrap_or_else(|| {
+                let color = if dark {
+                    Color32::LIGHT_GRAY
+                } else {
+                    Color32::DARK_GRAY
+                };
+                LayoutJob::simple(code.to_string(), font_id.clone(), color, f32::INFINITY)
+            })
+    }
+}
+
+impl Highlighter {
+    fn highlight(
+        &self,
+        font_id: &FontId,
+        theme: &str,
+        dark: bool,
+        code: &str,
+        language: &str,
+    ) -> Option<LayoutJob> {
+        let syntax = self
+            .syntaxes
+            .find_syntax_by_name(language)
+            .or_else(|| self.syntaxes.find_syntax_by_extension(language))?;
+        // an unknown theme falls back to egui_extras' defaults
+        let fallback = if dark {
+            "base16-mocha.dark"
+        } else {
+            "Solarized (light)"
+        };
+        let theme = self
+            .themes
+            .themes
+            .get(theme)
+            .or_else(|| self.themes.themes.get(fallback))?;
+
+        let mut lines = HighlightLines::new(syntax, theme);
+        let mut job = LayoutJob {
+            text: code.to_string(),
+            ..Default::default()
+        };
+        let mut start = 0;
+        for line in LinesWithEndings::from(code) {
+            for (style, text) in lines.highlight_line(line, &self.syntaxes).ok()? {
+                let fg = style.foreground;
+                let color = Color32::from_rgb(fg.r, fg.g, fg.b);
+                let underline = if style.font_style.contains(FontStyle::UNDERLINE) {
+                    egui::Stroke::new(1.0, color)
+                } else {
+                    egui::Stroke::NONE
+                };
+                job.sections.push(LayoutSection {
+                    leading_space: 0.0,
+                    byte_range: start..start + text.len(),
+                    format: TextFormat {
+                        font_id: font_id.clone(),
+                        color,
+                        italics: style.font_style.contains(FontStyle::ITALIC),
+                        underline,
+                        ..Default::default()
+                    },
+                });
+                start += text.len();
+            }
+        }
+        Some(job)
+    }
+}
+
+/// Highlights `code` as `language` (a syntax name or file extension) with the
+/// current style's syntax theme. Results are cached, so this can run every
+/// frame.
+pub fn highlight(ctx: &egui::Context, font_id: &FontId, code: &str, language: &str) -> LayoutJob {
+    type HighlightCache = egui::cache::FrameCache<LayoutJob, Highlighter>;
+
+    let style = AppStyle::current(ctx);
+    ctx.memory_mut(|memory| {
+        memory.caches.cache::<HighlightCache>().get((
+            font_id,
+            style.syntax_theme.as_str(),
+            style.dark,
+            code,
+            language,
+        ))
+    })
+}